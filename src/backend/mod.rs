@@ -0,0 +1,121 @@
+pub mod ndarray_backend;
+pub use ndarray_backend::NdarrayBackend;
+
+pub mod reference;
+pub use reference::ReferenceBackend;
+
+use ndarray::{ArrayD, Slice};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// Storage allocation and primitive kernels used by the ops in `crate::ops`.
+///
+/// Every backend stores tensors as `ArrayD<f32>`, so shape metadata
+/// (`insert_axis`, `shape()`, ...) is shared; what a backend owns is how
+/// buffers are allocated and how the kernels below are computed.
+///
+/// Some ops deliberately work on flat buffers instead, because their cost is
+/// index bookkeeping rather than arithmetic: einsum contraction, sort and
+/// topk, pooling windows, the im2col/col2im gathers of convolution (whose
+/// products do go through `matmul`) and the backward passes of the
+/// cumulative ops. They behave the same under every backend.
+pub trait Backend: Debug {
+    fn name(&self) -> &'static str;
+
+    // Storage
+
+    fn full(&self, shape: &[usize], value: f32) -> ArrayD<f32>;
+    fn array(&self, shape: &[usize], data: Vec<f32>) -> ArrayD<f32>;
+    fn broadcast(&self, x: &ArrayD<f32>, shape: &[usize]) -> ArrayD<f32>;
+
+    // Elementwise
+
+    fn map(&self, x: &ArrayD<f32>, f: &dyn Fn(f32) -> f32) -> ArrayD<f32>;
+    /// `map` that overwrites `x` instead of allocating.
+    fn map_inplace(&self, x: &mut ArrayD<f32>, f: &dyn Fn(f32) -> f32);
+    fn zip_map(&self, a: &ArrayD<f32>, b: &ArrayD<f32>, f: &dyn Fn(f32, f32) -> f32) -> ArrayD<f32>;
+
+    // Indexing
+
+    /// The region of `x` selected by one `Slice` per leading axis; later axes are kept whole.
+    fn slice(&self, x: &ArrayD<f32>, ranges: &[Slice]) -> ArrayD<f32>;
+    /// Copy of `x` with the region selected by `ranges` overwritten by `src`.
+    fn slice_scatter(&self, x: &ArrayD<f32>, src: &ArrayD<f32>, ranges: &[Slice]) -> ArrayD<f32>;
+
+    // Reduce
+
+    fn fold_axis(&self, x: &ArrayD<f32>, axis: usize, init: f32, f: &dyn Fn(f32, f32) -> f32) -> ArrayD<f32>;
+    fn fold_all(&self, x: &ArrayD<f32>, init: f32, f: &dyn Fn(f32, f32) -> f32) -> f32;
+    /// Inclusive scan along `axis`: `out[0] = x[0]`, `out[i] = f(out[i - 1], x[i])`.
+    fn scan_axis(&self, x: &ArrayD<f32>, axis: usize, f: &dyn Fn(f32, f32) -> f32) -> ArrayD<f32>;
+
+    // Matmul
+
+    /// `[M, K] x [K, N] -> [M, N]`.
+    fn matmul(&self, a: &ArrayD<f32>, b: &ArrayD<f32>) -> ArrayD<f32>;
+    /// `[B, M, K] x [B, K, N] -> [B, M, N]`.
+    fn batched_matmul(&self, a: &ArrayD<f32>, b: &ArrayD<f32>) -> ArrayD<f32>;
+
+    fn zeros(&self, shape: &[usize]) -> ArrayD<f32> {
+        self.full(shape, 0.0)
+    }
+
+    fn ones(&self, shape: &[usize]) -> ArrayD<f32> {
+        self.full(shape, 1.0)
+    }
+
+    fn sum_axis(&self, x: &ArrayD<f32>, axis: usize) -> ArrayD<f32> {
+        self.fold_axis(x, axis, 0.0, &|acc, v| acc + v)
+    }
+
+    fn sum_all(&self, x: &ArrayD<f32>) -> f32 {
+        self.fold_all(x, 0.0, &|acc, v| acc + v)
+    }
+}
+
+thread_local! {
+    static BACKEND: RefCell<Rc<dyn Backend>> = RefCell::new(Rc::new(NdarrayBackend));
+}
+
+/// The backend used by ops on this thread. Defaults to `NdarrayBackend`.
+pub fn current() -> Rc<dyn Backend> {
+    BACKEND.with(|b| b.borrow().clone())
+}
+
+pub fn set_backend(backend: Rc<dyn Backend>) {
+    BACKEND.with(|b| *b.borrow_mut() = backend);
+}
+
+/// Runs `f` with `backend` installed, restoring the previous backend afterwards.
+pub fn with_backend<R>(backend: Rc<dyn Backend>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Rc<dyn Backend>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            if let Some(previous) = self.0.take() {
+                set_backend(previous);
+            }
+        }
+    }
+
+    let _restore = Restore(Some(BACKEND.with(|b| b.replace(backend))));
+    f()
+}
+
+/// Numpy-style broadcast of two shapes, or `None` if they are incompatible.
+pub fn broadcast_shape(a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+    let ndim = a.len().max(b.len());
+    let mut shape = vec![0; ndim];
+    for i in 0..ndim {
+        let da = if i < ndim - a.len() { 1 } else { a[i - (ndim - a.len())] };
+        let db = if i < ndim - b.len() { 1 } else { b[i - (ndim - b.len())] };
+        shape[i] = match (da, db) {
+            (x, y) if x == y => x,
+            (1, y) => y,
+            (x, 1) => x,
+            _ => return None
+        };
+    }
+    Some(shape)
+}
@@ -0,0 +1,95 @@
+use crate::backend::{Backend, broadcast_shape};
+use ndarray::linalg::general_mat_mul;
+use ndarray::{Array3, ArrayD, Axis, Ix2, Ix3, IxDyn, Slice, Zip};
+
+/// Default backend, delegating every kernel to `ndarray`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NdarrayBackend;
+
+impl Backend for NdarrayBackend {
+    fn name(&self) -> &'static str { "ndarray" }
+
+    fn full(&self, shape: &[usize], value: f32) -> ArrayD<f32> {
+        ArrayD::from_elem(IxDyn(shape), value)
+    }
+
+    fn array(&self, shape: &[usize], data: Vec<f32>) -> ArrayD<f32> {
+        ArrayD::from_shape_vec(IxDyn(shape), data).expect("Data length does not match shape")
+    }
+
+    fn broadcast(&self, x: &ArrayD<f32>, shape: &[usize]) -> ArrayD<f32> {
+        x.broadcast(IxDyn(shape)).expect("Broadcast failed").to_owned()
+    }
+
+    fn map(&self, x: &ArrayD<f32>, f: &dyn Fn(f32) -> f32) -> ArrayD<f32> {
+        x.mapv(f)
+    }
+
+    fn map_inplace(&self, x: &mut ArrayD<f32>, f: &dyn Fn(f32) -> f32) {
+        x.mapv_inplace(f);
+    }
+
+    fn zip_map(&self, a: &ArrayD<f32>, b: &ArrayD<f32>, f: &dyn Fn(f32, f32) -> f32) -> ArrayD<f32> {
+        if a.shape() == b.shape() {
+            return Zip::from(a).and(b).map_collect(|&x, &y| f(x, y));
+        }
+
+        let shape = broadcast_shape(a.shape(), b.shape())
+            .unwrap_or_else(|| panic!("Cannot broadcast shapes {:?} and {:?}", a.shape(), b.shape()));
+        let a = a.broadcast(IxDyn(&shape)).unwrap();
+        let b = b.broadcast(IxDyn(&shape)).unwrap();
+        Zip::from(&a).and(&b).map_collect(|&x, &y| f(x, y))
+    }
+
+    fn slice(&self, x: &ArrayD<f32>, ranges: &[Slice]) -> ArrayD<f32> {
+        x.slice_each_axis(|ax| ranges.get(ax.axis.index()).copied().unwrap_or(Slice::from(..))).to_owned()
+    }
+
+    fn slice_scatter(&self, x: &ArrayD<f32>, src: &ArrayD<f32>, ranges: &[Slice]) -> ArrayD<f32> {
+        let mut out = x.clone();
+        out.slice_each_axis_mut(|ax| ranges.get(ax.axis.index()).copied().unwrap_or(Slice::from(..))).assign(src);
+        out
+    }
+
+    fn fold_axis(&self, x: &ArrayD<f32>, axis: usize, init: f32, f: &dyn Fn(f32, f32) -> f32) -> ArrayD<f32> {
+        x.fold_axis(Axis(axis), init, |&acc, &v| f(acc, v))
+    }
+
+    fn fold_all(&self, x: &ArrayD<f32>, init: f32, f: &dyn Fn(f32, f32) -> f32) -> f32 {
+        x.fold(init, |acc, &v| f(acc, v))
+    }
+
+    fn scan_axis(&self, x: &ArrayD<f32>, axis: usize, f: &dyn Fn(f32, f32) -> f32) -> ArrayD<f32> {
+        let mut out = x.clone();
+        for mut lane in out.lanes_mut(Axis(axis)) {
+            for i in 1..lane.len() {
+                lane[i] = f(lane[i - 1], lane[i]);
+            }
+        }
+        out
+    }
+
+    fn matmul(&self, a: &ArrayD<f32>, b: &ArrayD<f32>) -> ArrayD<f32> {
+        let a = a.view().into_dimensionality::<Ix2>().expect("matmul expects 2-D lhs");
+        let b = b.view().into_dimensionality::<Ix2>().expect("matmul expects 2-D rhs");
+        a.dot(&b).into_dyn()
+    }
+
+    fn batched_matmul(&self, a: &ArrayD<f32>, b: &ArrayD<f32>) -> ArrayD<f32> {
+        let a = a.view().into_dimensionality::<Ix3>().expect("batched_matmul expects 3-D lhs");
+        let b = b.view().into_dimensionality::<Ix3>().expect("batched_matmul expects 3-D rhs");
+        let mut out = Array3::zeros((a.shape()[0], a.shape()[1], b.shape()[2]));
+        for ((a, b), mut out) in a.outer_iter().zip(b.outer_iter()).zip(out.outer_iter_mut()) {
+            general_mat_mul(1.0, &a, &b, 0.0, &mut out);
+        }
+        out.into_dyn()
+    }
+
+    fn sum_axis(&self, x: &ArrayD<f32>, axis: usize) -> ArrayD<f32> {
+        x.sum_axis(Axis(axis))
+    }
+
+    fn sum_all(&self, x: &ArrayD<f32>) -> f32 {
+        x.sum()
+    }
+}
@@ -0,0 +1,216 @@
+use crate::backend::{Backend, broadcast_shape};
+use ndarray::{ArrayD, IxDyn, Slice};
+
+/// Straightforward loop-based kernels over row-major buffers.
+///
+/// Slow, but simple enough to be obviously correct, which makes it the
+/// backend to compare faster ones against in tests.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReferenceBackend;
+
+fn to_vec(x: &ArrayD<f32>) -> Vec<f32> {
+    x.iter().copied().collect()
+}
+
+fn row_major_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * shape[i + 1];
+    }
+    strides
+}
+
+/// Strides for reading `shape` as if it were broadcast to `target`.
+fn broadcast_strides(shape: &[usize], target: &[usize]) -> Vec<usize> {
+    let offset = target.len() - shape.len();
+    let strides = row_major_strides(shape);
+    (0..target.len())
+        .map(|i| {
+            if i < offset || shape[i - offset] == 1 { 0 } else { strides[i - offset] }
+        })
+        .collect()
+}
+
+/// Source offsets of every element of `target`, in row-major order.
+fn broadcast_offsets(shape: &[usize], target: &[usize]) -> Vec<usize> {
+    let strides = broadcast_strides(shape, target);
+    let size: usize = target.iter().product();
+    let mut offsets = Vec::with_capacity(size);
+    let mut index = vec![0; target.len()];
+    for _ in 0..size {
+        offsets.push(index.iter().zip(&strides).map(|(i, s)| i * s).sum());
+        for d in (0..target.len()).rev() {
+            index[d] += 1;
+            if index[d] < target[d] {
+                break;
+            }
+            index[d] = 0;
+        }
+    }
+    offsets
+}
+
+/// Positions along an axis of size `size` picked by `slice`, in output order.
+fn slice_indices(slice: Slice, size: usize) -> Vec<usize> {
+    let resolve = |i: isize| if i < 0 { (i + size as isize) as usize } else { i as usize };
+    let start = resolve(slice.start);
+    let end = slice.end.map_or(size, resolve).max(start);
+    let step = slice.step.unsigned_abs();
+    if slice.step > 0 {
+        (start..end).step_by(step).collect()
+    } else {
+        (start..end).rev().step_by(step).collect()
+    }
+}
+
+/// Shape of the region of `shape` selected by `ranges`, and the flat offsets
+/// of its elements in row-major order.
+fn slice_offsets(shape: &[usize], ranges: &[Slice]) -> (Vec<usize>, Vec<usize>) {
+    let strides = row_major_strides(shape);
+    let mut offsets = vec![0];
+    let mut out_shape = Vec::with_capacity(shape.len());
+    for (axis, &size) in shape.iter().enumerate() {
+        let indices = slice_indices(ranges.get(axis).copied().unwrap_or(Slice::from(..)), size);
+        out_shape.push(indices.len());
+        let stride = strides[axis];
+        offsets = offsets.iter().flat_map(|&o| indices.iter().map(move |&i| o + i * stride)).collect();
+    }
+    (out_shape, offsets)
+}
+
+impl Backend for ReferenceBackend {
+    fn name(&self) -> &'static str { "reference" }
+
+    fn full(&self, shape: &[usize], value: f32) -> ArrayD<f32> {
+        self.array(shape, vec![value; shape.iter().product()])
+    }
+
+    fn array(&self, shape: &[usize], data: Vec<f32>) -> ArrayD<f32> {
+        assert_eq!(data.len(), shape.iter().product::<usize>(), "Data length does not match shape");
+        ArrayD::from_shape_vec(IxDyn(shape), data).unwrap()
+    }
+
+    fn broadcast(&self, x: &ArrayD<f32>, shape: &[usize]) -> ArrayD<f32> {
+        let src = to_vec(x);
+        let data = broadcast_offsets(x.shape(), shape).into_iter().map(|o| src[o]).collect();
+        self.array(shape, data)
+    }
+
+    fn map(&self, x: &ArrayD<f32>, f: &dyn Fn(f32) -> f32) -> ArrayD<f32> {
+        let data = x.iter().map(|&v| f(v)).collect();
+        self.array(x.shape(), data)
+    }
+
+    fn map_inplace(&self, x: &mut ArrayD<f32>, f: &dyn Fn(f32) -> f32) {
+        for v in x.iter_mut() {
+            *v = f(*v);
+        }
+    }
+
+    fn zip_map(&self, a: &ArrayD<f32>, b: &ArrayD<f32>, f: &dyn Fn(f32, f32) -> f32) -> ArrayD<f32> {
+        let shape = broadcast_shape(a.shape(), b.shape())
+            .unwrap_or_else(|| panic!("Cannot broadcast shapes {:?} and {:?}", a.shape(), b.shape()));
+        let (a_data, b_data) = (to_vec(a), to_vec(b));
+        let data = broadcast_offsets(a.shape(), &shape)
+            .into_iter()
+            .zip(broadcast_offsets(b.shape(), &shape))
+            .map(|(i, j)| f(a_data[i], b_data[j]))
+            .collect();
+        self.array(&shape, data)
+    }
+
+    fn slice(&self, x: &ArrayD<f32>, ranges: &[Slice]) -> ArrayD<f32> {
+        let (shape, offsets) = slice_offsets(x.shape(), ranges);
+        let src = to_vec(x);
+        self.array(&shape, offsets.into_iter().map(|o| src[o]).collect())
+    }
+
+    fn slice_scatter(&self, x: &ArrayD<f32>, src: &ArrayD<f32>, ranges: &[Slice]) -> ArrayD<f32> {
+        let (shape, offsets) = slice_offsets(x.shape(), ranges);
+        assert_eq!(shape, src.shape(), "slice_scatter source does not match the selected region");
+        let mut data = to_vec(x);
+        for (o, &v) in offsets.into_iter().zip(src.iter()) {
+            data[o] = v;
+        }
+        self.array(x.shape(), data)
+    }
+
+    fn fold_axis(&self, x: &ArrayD<f32>, axis: usize, init: f32, f: &dyn Fn(f32, f32) -> f32) -> ArrayD<f32> {
+        let shape = x.shape();
+        let outer: usize = shape[..axis].iter().product();
+        let len = shape[axis];
+        let inner: usize = shape[axis + 1..].iter().product();
+        let src = to_vec(x);
+
+        let mut data = vec![init; outer * inner];
+        for o in 0..outer {
+            for k in 0..len {
+                for i in 0..inner {
+                    let acc = &mut data[o * inner + i];
+                    *acc = f(*acc, src[(o * len + k) * inner + i]);
+                }
+            }
+        }
+
+        let mut out_shape = shape.to_vec();
+        out_shape.remove(axis);
+        self.array(&out_shape, data)
+    }
+
+    fn fold_all(&self, x: &ArrayD<f32>, init: f32, f: &dyn Fn(f32, f32) -> f32) -> f32 {
+        x.iter().fold(init, |acc, &v| f(acc, v))
+    }
+
+    fn scan_axis(&self, x: &ArrayD<f32>, axis: usize, f: &dyn Fn(f32, f32) -> f32) -> ArrayD<f32> {
+        let shape = x.shape();
+        let outer: usize = shape[..axis].iter().product();
+        let len = shape[axis];
+        let inner: usize = shape[axis + 1..].iter().product();
+        let mut data = to_vec(x);
+
+        for o in 0..outer {
+            for k in 1..len {
+                for i in 0..inner {
+                    let (prev, cur) = ((o * len + k - 1) * inner + i, (o * len + k) * inner + i);
+                    data[cur] = f(data[prev], data[cur]);
+                }
+            }
+        }
+        self.array(shape, data)
+    }
+
+    fn matmul(&self, a: &ArrayD<f32>, b: &ArrayD<f32>) -> ArrayD<f32> {
+        assert!(a.ndim() == 2 && b.ndim() == 2, "matmul expects 2-D operands");
+        let (m, k) = (a.shape()[0], a.shape()[1]);
+        let n = b.shape()[1];
+        assert_eq!(k, b.shape()[0], "matmul inner dimensions do not match");
+
+        let (a_data, b_data) = (to_vec(a), to_vec(b));
+        let mut data = vec![0.0; m * n];
+        for i in 0..m {
+            for p in 0..k {
+                let lhs = a_data[i * k + p];
+                for j in 0..n {
+                    data[i * n + j] += lhs * b_data[p * n + j];
+                }
+            }
+        }
+        self.array(&[m, n], data)
+    }
+
+    fn batched_matmul(&self, a: &ArrayD<f32>, b: &ArrayD<f32>) -> ArrayD<f32> {
+        assert!(a.ndim() == 3 && b.ndim() == 3, "batched_matmul expects 3-D operands");
+        let (batch, m, k) = (a.shape()[0], a.shape()[1], a.shape()[2]);
+        let n = b.shape()[2];
+        assert_eq!([batch, k], [b.shape()[0], b.shape()[1]], "batched_matmul shapes do not match");
+
+        let (a_data, b_data) = (to_vec(a), to_vec(b));
+        let mut data = Vec::with_capacity(batch * m * n);
+        for i in 0..batch {
+            let lhs = self.array(&[m, k], a_data[i * m * k..(i + 1) * m * k].to_vec());
+            let rhs = self.array(&[k, n], b_data[i * k * n..(i + 1) * k * n].to_vec());
+            data.extend(self.matmul(&lhs, &rhs));
+        }
+        self.array(&[batch, m, n], data)
+    }
+}
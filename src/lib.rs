@@ -1,2 +1,5 @@
 pub mod tensor;
-pub mod ops;
\ No newline at end of file
+pub mod ops;
+pub mod backend;
+pub mod var;
+pub mod random;
\ No newline at end of file
@@ -1,25 +1,20 @@
-mod tensor;
-mod ops;
-
-use tensor::Tensor;
-use tensor::TensorOps;
-
-use ndarray::Array;
-use ops::*;
+use nanograd_rs::tensor;
+use nanograd_rs::tensor::Tensor;
+use nanograd_rs::var::Var;
 
 fn main() {
-    let a = Tensor::new(2.0, true);
-    let b = Tensor::new(-3.0, true);
-    let x = Tensor::new(Array::range(0.5, 2.0, 0.5).into_dyn(), true);
-    let c = &add(&mul(&a, &x), &b);
+    let a = Var::new(2.0, true);
+    let b = Var::new(-3.0, true);
+    let x = Var::from(Tensor::arange(0.5, 2.0, 0.5, true));
+    let c = &a * &x + &b;
 
-    let target = Tensor::new(Array::from_vec(vec![-2.0, -1.0, 0.0]).into_dyn(), true);
-    println!("{}", c.borrow());
+    let target = Var::from(tensor!([-2.0, -1.0, 0.0], true));
+    println!("{}", c);
 
-    let error = &sub(&target, &c);
+    let error = &target - &c;
     error.backward();
 
-    println!("Grad a: {:?}", a.borrow().grad);
-    println!("Grad b: {:?}", b.borrow().grad);
-    println!("Grad x: {:?}", x.borrow().grad);
-}
\ No newline at end of file
+    println!("Grad a: {:?}", a.grad());
+    println!("Grad b: {:?}", b.grad());
+    println!("Grad x: {:?}", x.grad());
+}
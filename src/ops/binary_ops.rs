@@ -1,14 +1,25 @@
 use crate::tensor::*;
-use crate::ops::op_defs::{Op, Add, Sub, Mul, Div};
+use crate::ops::op_defs::{Op, Add, Sub, Mul, Div, Pow, Maximum, Minimum};
+use crate::ops::shape_ops::sum_to_shape;
 use std::rc::Rc;
 
+/// Reduces each gradient back to the shape of the parent it belongs to,
+/// undoing any broadcasting done in the forward pass.
+fn unbroadcast(output: &TensorRef, grads: Vec<TensorData>) -> Vec<TensorData> {
+    let output_borrow = output.borrow();
+    output_borrow.parents.iter()
+        .zip(grads)
+        .map(|(parent, grad)| sum_to_shape(&grad, &parent.borrow().value().shape()))
+        .collect()
+}
+
 impl Op for Add {
     fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
-        &inputs[0].borrow().data + &inputs[1].borrow().data
+        inputs[0].borrow().value() + inputs[1].borrow().value()
     }
 
-    fn backward(&self, _output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
-        vec![grad_output.clone(), grad_output.clone()]
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        unbroadcast(output, vec![grad_output.clone(), grad_output.clone()])
     }
 
     fn name(&self) -> &'static str { "Add" }
@@ -16,11 +27,11 @@ impl Op for Add {
 
 impl Op for Sub {
     fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
-        &inputs[0].borrow().data - &inputs[1].borrow().data
+        inputs[0].borrow().value() - inputs[1].borrow().value()
     }
 
-    fn backward(&self, _output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
-        vec![grad_output.clone(), (-grad_output).clone()]
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        unbroadcast(output, vec![grad_output.clone(), (-grad_output).clone()])
     }
 
     fn name(&self) -> &'static str { "Sub" }
@@ -28,17 +39,17 @@ impl Op for Sub {
 
 impl Op for Mul {
     fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
-        &inputs[0].borrow().data * &inputs[1].borrow().data
+        inputs[0].borrow().value() * inputs[1].borrow().value()
     }
 
     fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
         let output_borrow = output.borrow();
-        let lhs = &output_borrow.parents[0].borrow().data;
-        let rhs = &output_borrow.parents[1].borrow().data;
-        vec![
+        let (lhs, rhs) = (output_borrow.parents[0].borrow(), output_borrow.parents[1].borrow());
+        let (lhs, rhs) = (lhs.value(), rhs.value());
+        unbroadcast(output, vec![
             grad_output * rhs, // dL/da = dL/dz * b
             grad_output * lhs  // dL/db = dL/dz * a
-        ]
+        ])
     }
 
     fn name(&self) -> &'static str { "Mul" }
@@ -46,14 +57,13 @@ impl Op for Mul {
 
 impl Op for Div {
     fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
-        let denominator = &inputs[1].borrow().data;
-        &inputs[0].borrow().data / denominator
+        inputs[0].borrow().value() / inputs[1].borrow().value()
     }
 
     fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
         let output_borrow = output.borrow();
-        let lhs = &output_borrow.parents[0].borrow().data;
-        let rhs = &output_borrow.parents[1].borrow().data;
+        let (lhs, rhs) = (output_borrow.parents[0].borrow(), output_borrow.parents[1].borrow());
+        let (lhs, rhs) = (lhs.value(), rhs.value());
 
         // if rhs.iter().any(|&x| x == 0.0) {
         //     panic!("division by zero");
@@ -61,26 +71,77 @@ impl Op for Div {
 
         let dzda = &TensorData::Scalar(1.0) / rhs;                    // dz/da = 1/b
         let dzdb = &(-lhs) / &(rhs * rhs);           // dz/db = -a/b^2
-        vec![
+        unbroadcast(output, vec![
             grad_output * &dzda, // dL/da = dL/dz * dz/da
             grad_output * &dzdb  // dL/db = dL/dz * dz/db
-        ]
+        ])
     }
 
     fn name(&self) -> &'static str { "Div" }
 }
 
-fn apply_binary_op(a: &TensorRef, b: &TensorRef, op: Rc<dyn Op>) -> TensorRef {
-    let data = op.forward(&[a, b]);
-    let requires_grad = a.borrow().requires_grad || b.borrow().requires_grad;
-    let result = Tensor::new(data, requires_grad);
+impl Op for Pow {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        inputs[0].borrow().value().zip_with(inputs[1].borrow().value(), f32::powf)
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let (base, exponent) = (output_borrow.parents[0].borrow(), output_borrow.parents[1].borrow());
+        let (base, exponent) = (base.value(), exponent.value());
+
+        // dz/da = b * a^(b - 1)
+        let dzda = base.zip_with(exponent, |a, b| if b == 0.0 { 0.0 } else { b * a.powf(b - 1.0) });
+        // dz/db = a^b * ln(a), taken as 0 at a = 0 where the limit along b is 0
+        let dzdb = base.zip_with(exponent, |a, b| if a == 0.0 { 0.0 } else { a.powf(b) * a.ln() });
+        unbroadcast(output, vec![
+            grad_output * &dzda,
+            grad_output * &dzdb
+        ])
+    }
+
+    fn name(&self) -> &'static str { "Pow" }
+}
+
+/// Routes `grad_output` to `a` where `a_wins(a, b)`, and to `b` everywhere else.
+fn select_grads(output: &TensorRef, grad_output: &TensorData, a_wins: impl Fn(f32, f32) -> bool) -> Vec<TensorData> {
+    let output_borrow = output.borrow();
+    let (lhs, rhs) = (output_borrow.parents[0].borrow(), output_borrow.parents[1].borrow());
+    let (lhs, rhs) = (lhs.value(), rhs.value());
+
+    let mask = lhs.zip_with(rhs, |a, b| a_wins(a, b) as u8 as f32);
+    unbroadcast(output, vec![
+        grad_output * &mask,
+        grad_output * &mask.map(|m| 1.0 - m)
+    ])
+}
+
+impl Op for Maximum {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        inputs[0].borrow().value().zip_with(inputs[1].borrow().value(), |a, b| if a.is_nan() || a > b { a } else { b })
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        select_grads(output, grad_output, |a, b| a.is_nan() || a > b)
+    }
 
-    if requires_grad {
-        result.borrow_mut().parents = vec![a.clone(), b.clone()];
-        result.borrow_mut().grad_fn = Some(op);
+    fn name(&self) -> &'static str { "Maximum" }
+}
+
+impl Op for Minimum {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        inputs[0].borrow().value().zip_with(inputs[1].borrow().value(), |a, b| if a.is_nan() || a < b { a } else { b })
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        select_grads(output, grad_output, |a, b| a.is_nan() || a < b)
     }
 
-    result
+    fn name(&self) -> &'static str { "Minimum" }
+}
+
+fn apply_binary_op(a: &TensorRef, b: &TensorRef, op: Rc<dyn Op>) -> TensorRef {
+    Tensor::from_op(op, &[a, b])
 }
 
 pub fn add(a: &TensorRef, b: &TensorRef) -> TensorRef {
@@ -97,4 +158,39 @@ pub fn mul(a: &TensorRef, b: &TensorRef) -> TensorRef {
 
 pub fn div(a: &TensorRef, b: &TensorRef) -> TensorRef {
     apply_binary_op(a, b, Rc::new(Div))
-}
\ No newline at end of file
+}
+
+/// Elementwise `a ^ b`, broadcasting like the other binary ops.
+pub fn pow(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    apply_binary_op(a, b, Rc::new(Pow))
+}
+
+/// Elementwise larger of `a` and `b`; ties send the gradient to `b`.
+pub fn maximum(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    apply_binary_op(a, b, Rc::new(Maximum))
+}
+
+/// Elementwise smaller of `a` and `b`; ties send the gradient to `b`.
+pub fn minimum(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    apply_binary_op(a, b, Rc::new(Minimum))
+}
+
+/// Limits `x` to `[min, max]` with broadcast tensor bounds, either of which may be omitted.
+/// At a bound, the gradient goes to the bound rather than `x`, as in `hardtanh`.
+pub fn clamp(x: &TensorRef, min: Option<&TensorRef>, max: Option<&TensorRef>) -> TensorRef {
+    assert!(min.is_some() || max.is_some(), "clamp needs at least one of min and max");
+    let lower = match min {
+        Some(min) => maximum(x, min),
+        None => x.clone()
+    };
+    match max {
+        Some(max) => minimum(&lower, max),
+        None => lower
+    }
+}
+
+/// `clamp` with constant bounds.
+pub fn clamp_scalar(x: &TensorRef, min: Option<f32>, max: Option<f32>) -> TensorRef {
+    let [min, max] = [min, max].map(|bound| bound.map(|v| Tensor::new(v, false)));
+    clamp(x, min.as_ref(), max.as_ref())
+}
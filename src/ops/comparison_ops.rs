@@ -0,0 +1,138 @@
+use crate::backend::{self, broadcast_shape};
+use crate::tensor::*;
+use crate::ops::op_defs::{Op, Compare, CompareKind, Where, MaskedFill};
+use crate::ops::shape_ops::sum_to_shape;
+use std::rc::Rc;
+
+impl CompareKind {
+    fn holds(&self, a: f32, b: f32) -> bool {
+        match self {
+            CompareKind::Eq => a == b,
+            CompareKind::Ne => a != b,
+            CompareKind::Lt => a < b,
+            CompareKind::Le => a <= b,
+            CompareKind::Gt => a > b,
+            CompareKind::Ge => a >= b
+        }
+    }
+}
+
+impl Op for Compare {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        inputs[0].borrow().value().zip_with(inputs[1].borrow().value(), |a, b| self.kind.holds(a, b) as u8 as f32)
+    }
+
+    fn backward(&self, _output: &TensorRef, _grad_output: &TensorData) -> Vec<TensorData> {
+        unreachable!("comparison outputs are not part of the autograd graph")
+    }
+
+    fn name(&self) -> &'static str { "Compare" }
+}
+
+impl Op for Where {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let [cond, a, b] = [0, 1, 2].map(|i| inputs[i].borrow().value().to_array());
+        let shape = broadcast_shape(cond.shape(), a.shape())
+            .and_then(|shape| broadcast_shape(&shape, b.shape()))
+            .unwrap_or_else(|| panic!(
+                "Cannot broadcast condition {:?} with branches {:?} and {:?}", cond.shape(), a.shape(), b.shape()
+            ));
+
+        let backend = backend::current();
+        let [cond, a, b] = [cond, a, b].map(|arr| backend.broadcast(&arr, &shape));
+        let data = cond.iter().zip(a.iter().zip(b.iter()))
+            .map(|(&c, (&a, &b))| if c != 0.0 { a } else { b })
+            .collect();
+        TensorData::from_array(backend.array(&shape, data))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let parents = &output_borrow.parents;
+        let shape = grad_output.shape();
+        let backend = backend::current();
+        let cond = TensorData::from_array(backend.broadcast(&parents[0].borrow().value().to_array(), &shape));
+
+        // Each branch gets the gradient where it was selected, summed back to its own shape.
+        let grad_a = grad_output.zip_with(&cond, |g, c| if c != 0.0 { g } else { 0.0 });
+        let grad_b = grad_output.zip_with(&cond, |g, c| if c != 0.0 { 0.0 } else { g });
+        vec![
+            TensorData::from_array(backend.zeros(&parents[0].borrow().value().shape())),
+            sum_to_shape(&grad_a, &parents[1].borrow().value().shape()),
+            sum_to_shape(&grad_b, &parents[2].borrow().value().shape())
+        ]
+    }
+
+    fn name(&self) -> &'static str { "Where" }
+}
+
+impl Op for MaskedFill {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let x = inputs[0].borrow().value().to_array();
+        let mask = inputs[1].borrow().value().to_array();
+        assert!(
+            broadcast_shape(mask.shape(), x.shape()).as_deref() == Some(x.shape()),
+            "Mask of shape {:?} does not broadcast to {:?}", mask.shape(), x.shape()
+        );
+
+        let backend = backend::current();
+        let mask = backend.broadcast(&mask, x.shape());
+        TensorData::from_array(backend.zip_map(&x, &mask, &|v, m| if m != 0.0 { self.value } else { v }))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let mask = output_borrow.parents[1].borrow();
+        let mask = mask.value();
+        vec![
+            grad_output.zip_with(mask, |g, m| if m != 0.0 { 0.0 } else { g }),
+            TensorData::from_array(backend::current().zeros(&mask.shape()))
+        ]
+    }
+
+    fn name(&self) -> &'static str { "MaskedFill" }
+}
+
+fn compare(a: &TensorRef, b: &TensorRef, kind: CompareKind) -> TensorRef {
+    Tensor::from_op_no_grad(Rc::new(Compare {kind}), &[a, b])
+}
+
+/// 1.0 where `a == b`, else 0.0; broadcasts like the arithmetic ops.
+pub fn eq(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    compare(a, b, CompareKind::Eq)
+}
+
+/// 1.0 where `a != b`, else 0.0.
+pub fn ne(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    compare(a, b, CompareKind::Ne)
+}
+
+/// 1.0 where `a < b`, else 0.0.
+pub fn lt(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    compare(a, b, CompareKind::Lt)
+}
+
+/// 1.0 where `a <= b`, else 0.0.
+pub fn le(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    compare(a, b, CompareKind::Le)
+}
+
+/// 1.0 where `a > b`, else 0.0.
+pub fn gt(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    compare(a, b, CompareKind::Gt)
+}
+
+/// 1.0 where `a >= b`, else 0.0.
+pub fn ge(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    compare(a, b, CompareKind::Ge)
+}
+
+/// `a` where `cond` is non-zero, `b` elsewhere, broadcasting all three.
+pub fn where_(cond: &TensorRef, a: &TensorRef, b: &TensorRef) -> TensorRef {
+    Tensor::from_op(Rc::new(Where), &[cond, a, b])
+}
+
+/// Copy of `x` with `value` wherever the (broadcast) `mask` is non-zero.
+pub fn masked_fill(x: &TensorRef, mask: &TensorRef, value: f32) -> TensorRef {
+    Tensor::from_op(Rc::new(MaskedFill {value}), &[x, mask])
+}
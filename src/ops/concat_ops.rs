@@ -0,0 +1,159 @@
+use crate::backend;
+use crate::tensor::*;
+use crate::ops::op_defs::{Op, MultiOp, Cat, Stack, Split, Chunk};
+use ndarray::{ArrayD, ArrayViewD, Axis, Slice};
+use std::rc::Rc;
+
+fn input_arrays(inputs: &[&TensorRef]) -> Vec<ArrayD<f32>> {
+    inputs.iter().map(|t| t.borrow().value().to_array()).collect()
+}
+
+/// Cuts `arr` along `axis` into consecutive pieces of the given sizes.
+fn split_array(arr: &ArrayD<f32>, axis: usize, sizes: &[usize]) -> Vec<ArrayD<f32>> {
+    let mut start = 0;
+    sizes.iter().map(|&size| {
+        let piece = arr.slice_axis(Axis(axis), Slice::from(start..start + size)).to_owned();
+        start += size;
+        piece
+    }).collect()
+}
+
+fn concatenate(arrays: &[ArrayD<f32>], axis: usize) -> ArrayD<f32> {
+    let views: Vec<ArrayViewD<f32>> = arrays.iter().map(|a| a.view()).collect();
+    ndarray::concatenate(Axis(axis), &views)
+        .unwrap_or_else(|e| panic!("Cannot concatenate tensors along axis {}: {}", axis, e))
+}
+
+impl Op for Cat {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        assert!(!inputs.is_empty(), "cat expects at least one tensor");
+        let arrays = input_arrays(inputs);
+        assert!(self.axis < arrays[0].ndim(), "Axis {} out of range for a {}-d tensor", self.axis, arrays[0].ndim());
+
+        TensorData::Tensor(concatenate(&arrays, self.axis))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let sizes: Vec<usize> = output.borrow().parents.iter()
+            .map(|p| p.borrow().value().shape()[self.axis])
+            .collect();
+
+        split_array(&grad_output.to_array(), self.axis, &sizes)
+            .into_iter()
+            .map(TensorData::Tensor)
+            .collect()
+    }
+
+    fn name(&self) -> &'static str { "Cat" }
+}
+
+impl Op for Stack {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        assert!(!inputs.is_empty(), "stack expects at least one tensor");
+        let arrays = input_arrays(inputs);
+        assert!(self.axis <= arrays[0].ndim(), "Axis {} out of range for stacking {}-d tensors", self.axis, arrays[0].ndim());
+
+        let views: Vec<ArrayViewD<f32>> = arrays.iter().map(|a| a.view()).collect();
+        let stacked = ndarray::stack(Axis(self.axis), &views)
+            .unwrap_or_else(|e| panic!("Cannot stack tensors along axis {}: {}", self.axis, e));
+        TensorData::Tensor(stacked)
+    }
+
+    fn backward(&self, _output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let grad = grad_output.to_array();
+        grad.axis_iter(Axis(self.axis))
+            .map(|g| TensorData::from_array(g.to_owned()))
+            .collect()
+    }
+
+    fn name(&self) -> &'static str { "Stack" }
+}
+
+/// Concatenates the output gradients of a split, using zeros for pieces without one.
+fn join_grads(inputs: &[TensorRef], grad_outputs: &[Option<TensorData>], axis: usize, sizes: &[usize]) -> Vec<TensorData> {
+    let mut shape = inputs[0].borrow().value().shape();
+    let pieces: Vec<ArrayD<f32>> = grad_outputs.iter().zip(sizes).map(|(grad, &size)| match grad {
+        Some(grad) => grad.to_array(),
+        None => {
+            shape[axis] = size;
+            backend::current().zeros(&shape)
+        }
+    }).collect();
+
+    vec![TensorData::Tensor(concatenate(&pieces, axis))]
+}
+
+fn axis_len(inputs: &[&TensorRef], axis: usize) -> usize {
+    let shape = inputs[0].borrow().value().shape();
+    assert!(axis < shape.len(), "Axis {} out of range for a {}-d tensor", axis, shape.len());
+    shape[axis]
+}
+
+impl Chunk {
+    fn sizes(&self, len: usize) -> Vec<usize> {
+        let (base, extra) = (len / self.chunks, len % self.chunks);
+        (0..self.chunks).map(|i| base + (i < extra) as usize).collect()
+    }
+}
+
+impl MultiOp for Split {
+    fn num_outputs(&self) -> usize { self.sizes.len() }
+
+    fn forward(&self, inputs: &[&TensorRef]) -> Vec<TensorData> {
+        let len = axis_len(inputs, self.axis);
+        assert_eq!(
+            self.sizes.iter().sum::<usize>(), len,
+            "Split sizes {:?} do not add up to the axis size {}", self.sizes, len
+        );
+
+        let arr = inputs[0].borrow().value().to_array();
+        split_array(&arr, self.axis, &self.sizes).into_iter().map(TensorData::Tensor).collect()
+    }
+
+    fn backward(&self, inputs: &[TensorRef], grad_outputs: &[Option<TensorData>]) -> Vec<TensorData> {
+        join_grads(inputs, grad_outputs, self.axis, &self.sizes)
+    }
+
+    fn name(&self) -> &'static str { "Split" }
+}
+
+impl MultiOp for Chunk {
+    fn num_outputs(&self) -> usize { self.chunks }
+
+    fn forward(&self, inputs: &[&TensorRef]) -> Vec<TensorData> {
+        let sizes = self.sizes(axis_len(inputs, self.axis));
+        let arr = inputs[0].borrow().value().to_array();
+        split_array(&arr, self.axis, &sizes).into_iter().map(TensorData::Tensor).collect()
+    }
+
+    fn backward(&self, inputs: &[TensorRef], grad_outputs: &[Option<TensorData>]) -> Vec<TensorData> {
+        let sizes = self.sizes(inputs[0].borrow().value().shape()[self.axis]);
+        join_grads(inputs, grad_outputs, self.axis, &sizes)
+    }
+
+    fn name(&self) -> &'static str { "Chunk" }
+}
+
+/// Concatenates `tensors` along an existing `axis`.
+pub fn cat(tensors: &[TensorRef], axis: usize) -> TensorRef {
+    let inputs: Vec<&TensorRef> = tensors.iter().collect();
+    Tensor::from_op(Rc::new(Cat {axis}), &inputs)
+}
+
+/// Stacks equally shaped `tensors` along a new `axis`.
+pub fn stack(tensors: &[TensorRef], axis: usize) -> TensorRef {
+    let inputs: Vec<&TensorRef> = tensors.iter().collect();
+    Tensor::from_op(Rc::new(Stack {axis}), &inputs)
+}
+
+/// Splits `a` along `axis` into consecutive pieces of the given `sizes`.
+pub fn split(a: &TensorRef, sizes: &[usize], axis: usize) -> Vec<TensorRef> {
+    Tensor::from_multi_op(Rc::new(Split {sizes: sizes.to_vec(), axis}), &[a])
+}
+
+/// Splits `a` along `axis` into exactly `chunks` pieces; when the axis does
+/// not divide evenly the first pieces are one element larger.
+pub fn chunk(a: &TensorRef, chunks: usize, axis: usize) -> Vec<TensorRef> {
+    assert!(chunks > 0, "chunk expects at least one chunk");
+    Tensor::from_multi_op(Rc::new(Chunk {chunks, axis}), &[a])
+}
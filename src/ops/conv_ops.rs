@@ -0,0 +1,374 @@
+use crate::backend;
+use crate::tensor::*;
+use crate::ops::op_defs::{Op, Conv, ConvOptions, ConvTranspose};
+use ndarray::ArrayD;
+use std::rc::Rc;
+
+/// Index bookkeeping relating a (padded, dilated, strided) kernel window to
+/// the positions of a larger `input` grid and a smaller `output` grid.
+pub(crate) struct ConvGeometry {
+    pub input: Vec<usize>,
+    pub output: Vec<usize>,
+    /// `table[k * output_len + l]` is the flat input position read by kernel
+    /// offset `k` at output position `l`, or `None` where it falls in padding.
+    table: Vec<Option<usize>>
+}
+
+fn unravel(mut flat: usize, shape: &[usize]) -> Vec<usize> {
+    let mut index = vec![0; shape.len()];
+    for d in (0..shape.len()).rev() {
+        index[d] = flat % shape[d];
+        flat /= shape[d];
+    }
+    index
+}
+
+impl ConvGeometry {
+    pub fn new(input: &[usize], kernel: &[usize], options: &ConvOptions) -> ConvGeometry {
+        let ConvOptions { stride, padding, dilation, .. } = *options;
+        assert!(stride > 0 && dilation > 0, "stride and dilation must be positive");
+
+        let output: Vec<usize> = input.iter().zip(kernel).map(|(&i, &k)| {
+            let span = dilation * (k - 1) + 1;
+            assert!(
+                i + 2 * padding >= span,
+                "Kernel of size {:?} (dilation {}) does not fit input of size {:?} with padding {}", kernel, dilation, input, padding
+            );
+            (i + 2 * padding - span) / stride + 1
+        }).collect();
+        ConvGeometry::with_output(input, &output, kernel, options)
+    }
+
+    /// Geometry over an explicit output grid, which may stop short of the last
+    /// window that would still fit the input.
+    pub fn with_output(input: &[usize], output: &[usize], kernel: &[usize], options: &ConvOptions) -> ConvGeometry {
+        let ConvOptions { stride, padding, dilation, .. } = *options;
+        let kernel_len: usize = kernel.iter().product();
+        let output_len: usize = output.iter().product();
+        let mut table = Vec::with_capacity(kernel_len * output_len);
+        for k in 0..kernel_len {
+            let k_index = unravel(k, kernel);
+            for l in 0..output_len {
+                let l_index = unravel(l, output);
+                let mut flat = 0;
+                let mut inside = true;
+                for d in 0..input.len() {
+                    let pos = (l_index[d] * stride + k_index[d] * dilation) as isize - padding as isize;
+                    if pos < 0 || pos as usize >= input[d] {
+                        inside = false;
+                        break;
+                    }
+                    flat = flat * input[d] + pos as usize;
+                }
+                table.push(if inside { Some(flat) } else { None });
+            }
+        }
+
+        ConvGeometry { input: input.to_vec(), output: output.to_vec(), table }
+    }
+
+    pub fn input_len(&self) -> usize {
+        self.input.iter().product()
+    }
+
+    pub fn output_len(&self) -> usize {
+        self.output.iter().product()
+    }
+
+    /// Unfolds `channels` input planes into a `[channels * K, L]` column matrix.
+    pub fn im2col(&self, x: &[f32], channels: usize) -> ArrayD<f32> {
+        let (input_len, output_len) = (self.input_len(), self.output_len());
+        let kernel_len = self.table.len() / output_len;
+        let mut cols = Vec::with_capacity(channels * self.table.len());
+        for c in 0..channels {
+            let plane = &x[c * input_len..(c + 1) * input_len];
+            cols.extend(self.table.iter().map(|pos| pos.map_or(0.0, |p| plane[p])));
+        }
+        backend::current().array(&[channels * kernel_len, output_len], cols)
+    }
+
+    /// Adjoint of `im2col`: adds each column entry back onto the input position it was read from.
+    pub fn col2im(&self, cols: &ArrayD<f32>, channels: usize, x: &mut [f32]) {
+        let input_len = self.input_len();
+        let cols: Vec<f32> = cols.iter().copied().collect();
+        for c in 0..channels {
+            let plane = &mut x[c * input_len..(c + 1) * input_len];
+            let block = &cols[c * self.table.len()..(c + 1) * self.table.len()];
+            for (pos, &v) in self.table.iter().zip(block) {
+                if let Some(p) = pos {
+                    plane[*p] += v;
+                }
+            }
+        }
+    }
+}
+
+fn to_vec(arr: &ArrayD<f32>) -> Vec<f32> {
+    arr.iter().copied().collect()
+}
+
+/// Rows `rows` of a row-major `[_, cols]` buffer as a 2-D array.
+pub(crate) fn block(data: &[f32], rows: std::ops::Range<usize>, cols: usize) -> ArrayD<f32> {
+    let len = rows.len();
+    backend::current().array(&[len, cols], data[rows.start * cols..rows.end * cols].to_vec())
+}
+
+/// Shapes of a convolution's operands: `(batch, in_channels, out_channels, spatial, kernel)`.
+fn conv_shapes(x: &[usize], weight: &[usize], spatial_dims: usize, groups: usize) -> (usize, usize, usize, Vec<usize>, Vec<usize>) {
+    assert_eq!(x.len(), spatial_dims + 2, "conv{}d expects an input of shape [N, C, ...] with {} spatial dims, got {:?}", spatial_dims, spatial_dims, x);
+    assert_eq!(weight.len(), spatial_dims + 2, "conv{}d expects a weight with {} dims, got {:?}", spatial_dims, spatial_dims + 2, weight);
+    assert!(groups > 0, "groups must be positive");
+    (x[0], x[1], weight[0], x[2..].to_vec(), weight[2..].to_vec())
+}
+
+impl Op for Conv {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let x = inputs[0].borrow().value().to_array();
+        let w = inputs[1].borrow().value().to_array();
+        let groups = self.options.groups;
+        let (n, c, o, spatial, kernel) = conv_shapes(x.shape(), w.shape(), self.spatial_dims, groups);
+        assert!(c % groups == 0 && o % groups == 0, "Channels {} -> {} are not divisible by groups {}", c, o, groups);
+        let (cg, og) = (c / groups, o / groups);
+        assert_eq!(w.shape()[1], cg, "Weight expects {} input channels per group, input has {}", w.shape()[1], cg);
+
+        let geo = ConvGeometry::new(&spatial, &kernel, &self.options);
+        let (input_len, output_len) = (geo.input_len(), geo.output_len());
+        let k = cg * kernel.iter().product::<usize>();
+        let (x_data, w_data) = (to_vec(&x), to_vec(&w));
+
+        let mut out = vec![0.0; n * o * output_len];
+        for b in 0..n {
+            for g in 0..groups {
+                let x_start = (b * c + g * cg) * input_len;
+                let cols = geo.im2col(&x_data[x_start..x_start + cg * input_len], cg);
+                let result = backend::current().matmul(&block(&w_data, g * og..(g + 1) * og, k), &cols);
+
+                let out_start = (b * o + g * og) * output_len;
+                for (dst, v) in out[out_start..out_start + og * output_len].iter_mut().zip(result.iter()) {
+                    *dst = *v;
+                }
+            }
+        }
+
+        if let Some(bias) = inputs.get(2) {
+            let bias = to_vec(&bias.borrow().value().to_array());
+            assert_eq!(bias.len(), o, "Bias has {} entries for {} output channels", bias.len(), o);
+            for (i, v) in out.iter_mut().enumerate() {
+                *v += bias[(i / output_len) % o];
+            }
+        }
+
+        let mut shape = vec![n, o];
+        shape.extend(&geo.output);
+        TensorData::Tensor(backend::current().array(&shape, out))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let parents = &output_borrow.parents;
+        let x = parents[0].borrow().value().to_array();
+        let w = parents[1].borrow().value().to_array();
+        let groups = self.options.groups;
+        let (n, c, o, spatial, kernel) = conv_shapes(x.shape(), w.shape(), self.spatial_dims, groups);
+        let (cg, og) = (c / groups, o / groups);
+
+        let geo = ConvGeometry::new(&spatial, &kernel, &self.options);
+        let (input_len, output_len) = (geo.input_len(), geo.output_len());
+        let k = cg * kernel.iter().product::<usize>();
+        let backend = backend::current();
+        let (x_data, w_data, grad) = (to_vec(&x), to_vec(&w), to_vec(&grad_output.to_array()));
+
+        let mut grad_x = vec![0.0; x_data.len()];
+        let mut grad_w = vec![0.0; w_data.len()];
+        for b in 0..n {
+            for g in 0..groups {
+                let x_start = (b * c + g * cg) * input_len;
+                let grad_g = block(&grad, b * o + g * og..b * o + (g + 1) * og, output_len);
+
+                // dL/dW_g += dL/dY_g @ cols^T
+                let cols = geo.im2col(&x_data[x_start..x_start + cg * input_len], cg);
+                let cols_t = cols.t().to_owned();
+                let gw = backend.matmul(&grad_g, &cols_t);
+                for (dst, v) in grad_w[g * og * k..(g + 1) * og * k].iter_mut().zip(gw.iter()) {
+                    *dst += *v;
+                }
+
+                // dL/dX_g = col2im(W_g^T @ dL/dY_g)
+                let w_t = block(&w_data, g * og..(g + 1) * og, k).t().to_owned();
+                let grad_cols = backend.matmul(&w_t, &grad_g);
+                geo.col2im(&grad_cols, cg, &mut grad_x[x_start..x_start + cg * input_len]);
+            }
+        }
+
+        let mut grads = vec![
+            TensorData::Tensor(backend.array(x.shape(), grad_x)),
+            TensorData::Tensor(backend.array(w.shape(), grad_w))
+        ];
+        if parents.len() > 2 {
+            let mut grad_b = vec![0.0; o];
+            for (i, v) in grad.iter().enumerate() {
+                grad_b[(i / output_len) % o] += v;
+            }
+            grads.push(TensorData::Tensor(backend.array(&[o], grad_b)));
+        }
+        grads
+    }
+
+    fn name(&self) -> &'static str { "Conv" }
+}
+
+impl ConvTranspose {
+    /// Geometry of the equivalent forward convolution, mapping this op's
+    /// (larger) output grid back onto its input grid.
+    fn geometry(&self, spatial: &[usize], kernel: &[usize]) -> ConvGeometry {
+        let ConvOptions { stride, padding, dilation, .. } = self.options;
+        assert!(
+            self.output_padding < stride || self.output_padding < dilation,
+            "output_padding {} must be smaller than stride {} or dilation {}", self.output_padding, stride, dilation
+        );
+
+        let output: Vec<usize> = spatial.iter().zip(kernel).map(|(&i, &k)| {
+            let size = (i as isize - 1) * stride as isize - 2 * padding as isize
+                + (dilation * (k - 1) + self.output_padding + 1) as isize;
+            assert!(size > 0, "conv_transpose output size would be {} for input of size {:?}", size, spatial);
+            size as usize
+        }).collect();
+
+        // With output_padding >= stride the padded output would fit extra
+        // windows, so pin the window grid to the transposed input.
+        ConvGeometry::with_output(&output, spatial, kernel, &self.options)
+    }
+}
+
+impl Op for ConvTranspose {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let x = inputs[0].borrow().value().to_array();
+        let w = inputs[1].borrow().value().to_array();
+        let groups = self.options.groups;
+        let (n, c, _, spatial, kernel) = conv_shapes(x.shape(), w.shape(), self.spatial_dims, groups);
+        assert_eq!(w.shape()[0], c, "Weight expects {} input channels, input has {}", w.shape()[0], c);
+        assert!(c % groups == 0, "Input channels {} are not divisible by groups {}", c, groups);
+        let (cg, og) = (c / groups, w.shape()[1]);
+        let o = og * groups;
+
+        let geo = self.geometry(&spatial, &kernel);
+        let (input_len, output_len) = (geo.output_len(), geo.input_len());
+        let k = og * kernel.iter().product::<usize>();
+        let (x_data, w_data) = (to_vec(&x), to_vec(&w));
+        let backend = backend::current();
+
+        let mut out = vec![0.0; n * o * output_len];
+        for b in 0..n {
+            for g in 0..groups {
+                // Y_g = col2im(W_g^T @ X_g)
+                let x_g = block(&x_data, b * c + g * cg..b * c + (g + 1) * cg, input_len);
+                let w_t = block(&w_data, g * cg..(g + 1) * cg, k).t().to_owned();
+                let cols = backend.matmul(&w_t, &x_g);
+
+                let out_start = (b * o + g * og) * output_len;
+                geo.col2im(&cols, og, &mut out[out_start..out_start + og * output_len]);
+            }
+        }
+
+        if let Some(bias) = inputs.get(2) {
+            let bias = to_vec(&bias.borrow().value().to_array());
+            assert_eq!(bias.len(), o, "Bias has {} entries for {} output channels", bias.len(), o);
+            for (i, v) in out.iter_mut().enumerate() {
+                *v += bias[(i / output_len) % o];
+            }
+        }
+
+        let mut shape = vec![n, o];
+        shape.extend(&geo.input);
+        TensorData::Tensor(backend.array(&shape, out))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let parents = &output_borrow.parents;
+        let x = parents[0].borrow().value().to_array();
+        let w = parents[1].borrow().value().to_array();
+        let groups = self.options.groups;
+        let (n, c, _, spatial, kernel) = conv_shapes(x.shape(), w.shape(), self.spatial_dims, groups);
+        let (cg, og) = (c / groups, w.shape()[1]);
+        let o = og * groups;
+
+        let geo = self.geometry(&spatial, &kernel);
+        let (input_len, output_len) = (geo.output_len(), geo.input_len());
+        let k = og * kernel.iter().product::<usize>();
+        let backend = backend::current();
+        let (x_data, w_data, grad) = (to_vec(&x), to_vec(&w), to_vec(&grad_output.to_array()));
+
+        let mut grad_x = vec![0.0; x_data.len()];
+        let mut grad_w = vec![0.0; w_data.len()];
+        for b in 0..n {
+            for g in 0..groups {
+                let grad_start = (b * o + g * og) * output_len;
+                let grad_cols = geo.im2col(&grad[grad_start..grad_start + og * output_len], og);
+
+                // dL/dX_g = W_g @ im2col(dL/dY_g)
+                let gx = backend.matmul(&block(&w_data, g * cg..(g + 1) * cg, k), &grad_cols);
+                let x_start = (b * c + g * cg) * input_len;
+                for (dst, v) in grad_x[x_start..x_start + cg * input_len].iter_mut().zip(gx.iter()) {
+                    *dst += *v;
+                }
+
+                // dL/dW_g += X_g @ im2col(dL/dY_g)^T
+                let x_g = block(&x_data, b * c + g * cg..b * c + (g + 1) * cg, input_len);
+                let gw = backend.matmul(&x_g, &grad_cols.t().to_owned());
+                for (dst, v) in grad_w[g * cg * k..(g + 1) * cg * k].iter_mut().zip(gw.iter()) {
+                    *dst += *v;
+                }
+            }
+        }
+
+        let mut grads = vec![
+            TensorData::Tensor(backend.array(x.shape(), grad_x)),
+            TensorData::Tensor(backend.array(w.shape(), grad_w))
+        ];
+        if parents.len() > 2 {
+            let mut grad_b = vec![0.0; o];
+            for (i, v) in grad.iter().enumerate() {
+                grad_b[(i / output_len) % o] += v;
+            }
+            grads.push(TensorData::Tensor(backend.array(&[o], grad_b)));
+        }
+        grads
+    }
+
+    fn name(&self) -> &'static str { "ConvTranspose" }
+}
+
+fn apply_conv_op(x: &TensorRef, weight: &TensorRef, bias: Option<&TensorRef>, op: Rc<dyn Op>) -> TensorRef {
+    match bias {
+        Some(bias) => Tensor::from_op(op, &[x, weight, bias]),
+        None => Tensor::from_op(op, &[x, weight])
+    }
+}
+
+/// 1-D convolution of `x: [N, C, L]` with `weight: [O, C / groups, K]` and optional `bias: [O]`.
+pub fn conv1d(x: &TensorRef, weight: &TensorRef, bias: Option<&TensorRef>, options: ConvOptions) -> TensorRef {
+    apply_conv_op(x, weight, bias, Rc::new(Conv {spatial_dims: 1, options}))
+}
+
+/// 2-D convolution of `x: [N, C, H, W]` with `weight: [O, C / groups, KH, KW]` and optional `bias: [O]`.
+pub fn conv2d(x: &TensorRef, weight: &TensorRef, bias: Option<&TensorRef>, options: ConvOptions) -> TensorRef {
+    apply_conv_op(x, weight, bias, Rc::new(Conv {spatial_dims: 2, options}))
+}
+
+/// 3-D convolution of `x: [N, C, D, H, W]` with `weight: [O, C / groups, KD, KH, KW]` and optional `bias: [O]`.
+pub fn conv3d(x: &TensorRef, weight: &TensorRef, bias: Option<&TensorRef>, options: ConvOptions) -> TensorRef {
+    apply_conv_op(x, weight, bias, Rc::new(Conv {spatial_dims: 3, options}))
+}
+
+/// Transposed 1-D convolution of `x: [N, C, L]` with `weight: [C, O / groups, K]`
+/// and optional `bias: [O]`; `output_padding` extends one side of the output.
+pub fn conv_transpose1d(x: &TensorRef, weight: &TensorRef, bias: Option<&TensorRef>, options: ConvOptions, output_padding: usize) -> TensorRef {
+    apply_conv_op(x, weight, bias, Rc::new(ConvTranspose {spatial_dims: 1, options, output_padding}))
+}
+
+/// Transposed 2-D convolution of `x: [N, C, H, W]` with `weight: [C, O / groups, KH, KW]`
+/// and optional `bias: [O]`; `output_padding` extends one side of each output dim.
+pub fn conv_transpose2d(x: &TensorRef, weight: &TensorRef, bias: Option<&TensorRef>, options: ConvOptions, output_padding: usize) -> TensorRef {
+    apply_conv_op(x, weight, bias, Rc::new(ConvTranspose {spatial_dims: 2, options, output_padding}))
+}
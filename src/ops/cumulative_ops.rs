@@ -0,0 +1,151 @@
+use crate::backend;
+use crate::tensor::*;
+use crate::ops::op_defs::{Op, Cumulative, CumulativeKind};
+use crate::ops::reduction_ops::{normalize_axes, ReduceLayout};
+use std::rc::Rc;
+
+/// Running position of the max (`largest`) or min, preferring the most recent on ties.
+fn running_arg(lane: &[f32], largest: bool) -> Vec<usize> {
+    let mut best = 0;
+    (0..lane.len()).map(|i| {
+        let better = if largest { lane[i] >= lane[best] } else { lane[i] <= lane[best] };
+        if better {
+            best = i;
+        }
+        best
+    }).collect()
+}
+
+impl CumulativeKind {
+    /// One step of the scan: folds the next element `x` into the running value `acc`.
+    fn combine(&self, acc: f32, x: f32) -> f32 {
+        match self {
+            CumulativeKind::Sum => acc + x,
+            CumulativeKind::Prod => acc * x,
+            // Same tie and NaN handling as `running_arg`.
+            CumulativeKind::Max => if x >= acc { x } else { acc },
+            CumulativeKind::Min => if x <= acc { x } else { acc },
+            CumulativeKind::LogSumExp => {
+                // log(exp(acc) + exp(x)), shifted by the larger of the two
+                let hi = acc.max(x);
+                if hi == f32::NEG_INFINITY { acc } else { hi + ((acc - hi).exp() + (x - hi).exp()).ln() }
+            }
+        }
+    }
+
+    /// Gradient of one lane `x` given its scan `y` and upstream gradient `g`.
+    fn backward(&self, x: &[f32], y: &[f32], g: &[f32]) -> Vec<f32> {
+        let n = x.len();
+        let mut grad = vec![0.0; n];
+        match self {
+            CumulativeKind::Sum => {
+                // Reverse cumsum of g.
+                let mut acc = 0.0;
+                for i in (0..n).rev() {
+                    acc += g[i];
+                    grad[i] = acc;
+                }
+            },
+            CumulativeKind::Prod => {
+                // Before the first zero z, dy_i/dx_k = y_i / x_k for i >= k (and y_i = 0 from z on).
+                let z = x.iter().position(|&v| v == 0.0).unwrap_or(n);
+                let mut acc = 0.0;
+                for k in (0..z).rev() {
+                    acc += g[k] * y[k];
+                    grad[k] = acc / x[k];
+                }
+                // At z itself, y_i / x_z is the product of every other x_j with j <= i.
+                if z < n {
+                    let mut others = if z == 0 { 1.0 } else { y[z - 1] };
+                    let mut acc = 0.0;
+                    for i in z..n {
+                        if i > z {
+                            others *= x[i];
+                        }
+                        acc += g[i] * others;
+                    }
+                    grad[z] = acc;
+                }
+            },
+            CumulativeKind::Max | CumulativeKind::Min => {
+                for (i, k) in running_arg(x, *self == CumulativeKind::Max).into_iter().enumerate() {
+                    grad[k] += g[i];
+                }
+            },
+            CumulativeKind::LogSumExp => {
+                // grad_k = exp(x_k - y_k) * r_k with r_k = sum_{i >= k} g_i * exp(y_k - y_i),
+                // where y is non-decreasing so every exponent is <= 0.
+                let mut r = 0.0;
+                for k in (0..n).rev() {
+                    if y[k] == f32::NEG_INFINITY {
+                        // Every earlier y is -inf too, so nothing before k gets a gradient.
+                        break;
+                    }
+                    let decay = if k + 1 < n { (y[k] - y[k + 1]).exp() } else { 0.0 };
+                    r = g[k] + decay * r;
+                    grad[k] = (x[k] - y[k]).exp() * r;
+                }
+            }
+        }
+        grad
+    }
+}
+
+impl Op for Cumulative {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = inputs[0].borrow().value().to_array();
+        let axis = normalize_axes(&Some(vec![self.axis]), arr.ndim())[0];
+        TensorData::from_array(backend::current().scan_axis(&arr, axis, &|acc, x| self.kind.combine(acc, x)))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let x = output_borrow.parents[0].borrow().value().to_array();
+        let layout = ReduceLayout::new(x.shape(), &Some(vec![self.axis]), true);
+        if layout.inner == 0 {
+            return vec![TensorData::from_array(backend::current().zeros(x.shape()))];
+        }
+
+        let (x, y, g) = (
+            layout.lanes(&x),
+            layout.lanes(&output_borrow.value().to_array()),
+            layout.lanes(&grad_output.to_array())
+        );
+        let grad = x.chunks(layout.inner)
+            .zip(y.chunks(layout.inner).zip(g.chunks(layout.inner)))
+            .flat_map(|(x, (y, g))| self.kind.backward(x, y, g))
+            .collect();
+        vec![TensorData::from_array(layout.restore(grad))]
+    }
+
+    fn name(&self) -> &'static str { "Cumulative" }
+}
+
+fn apply_cumulative_op(a: &TensorRef, kind: CumulativeKind, axis: isize) -> TensorRef {
+    Tensor::from_op(Rc::new(Cumulative {kind, axis}), &[a])
+}
+
+/// Running sum along `axis`.
+pub fn cumsum(a: &TensorRef, axis: isize) -> TensorRef {
+    apply_cumulative_op(a, CumulativeKind::Sum, axis)
+}
+
+/// Running product along `axis`; the gradient stays finite when `a` contains zeros.
+pub fn cumprod(a: &TensorRef, axis: isize) -> TensorRef {
+    apply_cumulative_op(a, CumulativeKind::Prod, axis)
+}
+
+/// Running maximum along `axis`.
+pub fn cummax(a: &TensorRef, axis: isize) -> TensorRef {
+    apply_cumulative_op(a, CumulativeKind::Max, axis)
+}
+
+/// Running minimum along `axis`.
+pub fn cummin(a: &TensorRef, axis: isize) -> TensorRef {
+    apply_cumulative_op(a, CumulativeKind::Min, axis)
+}
+
+/// Running `log(sum(exp(a)))` along `axis`, computed without overflow.
+pub fn logcumsumexp(a: &TensorRef, axis: isize) -> TensorRef {
+    apply_cumulative_op(a, CumulativeKind::LogSumExp, axis)
+}
@@ -0,0 +1,281 @@
+use crate::backend;
+use crate::tensor::*;
+use crate::ops::op_defs::{Op, SliceOp, Index, Gather, ScatterAdd, IndexSelect, IndexAdd};
+use ndarray::{ArrayD, Axis, Slice};
+use std::rc::Rc;
+
+fn input_array(inputs: &[&TensorRef], i: usize) -> ArrayD<f32> {
+    match inputs[i].borrow().value() {
+        TensorData::Tensor(arr) => arr.clone(),
+        TensorData::Scalar(_) => panic!("Cannot index into a scalar")
+    }
+}
+
+fn parent_shape(output: &TensorRef, i: usize) -> Vec<usize> {
+    output.borrow().parents[i].borrow().value().shape()
+}
+
+impl Op for SliceOp {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = input_array(inputs, 0);
+        if let Some(last) = self.ranges.len().checked_sub(1) {
+            check_axis(last, arr.ndim());
+        }
+        for (axis, (range, &size)) in self.ranges.iter().zip(arr.shape()).enumerate() {
+            // Negative positions count from the end of the axis.
+            let in_bounds = |i: isize| {
+                let i = if i < 0 { i + size as isize } else { i };
+                0 <= i && i as usize <= size
+            };
+            assert!(
+                in_bounds(range.start) && range.end.is_none_or(in_bounds),
+                "Slice {:?} out of range for axis {} of size {}", range, axis, size
+            );
+        }
+
+        TensorData::Tensor(backend::current().slice(&arr, &self.ranges))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let backend = backend::current();
+        let zeros = backend.zeros(&parent_shape(output, 0));
+        vec![TensorData::Tensor(backend.slice_scatter(&zeros, &grad_output.to_array(), &self.ranges))]
+    }
+
+    fn name(&self) -> &'static str { "Slice" }
+}
+
+impl Op for Index {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = input_array(inputs, 0);
+        check_axis(self.axis, arr.ndim());
+        assert!(
+            self.index < arr.shape()[self.axis],
+            "Index {} out of range for axis {} of size {}", self.index, self.axis, arr.shape()[self.axis]
+        );
+
+        TensorData::from_array(arr.index_axis(Axis(self.axis), self.index).to_owned())
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let mut grad = backend::current().zeros(&parent_shape(output, 0));
+        grad.index_axis_mut(Axis(self.axis), self.index).assign(&grad_output.to_array());
+
+        vec![TensorData::Tensor(grad)]
+    }
+
+    fn name(&self) -> &'static str { "Index" }
+}
+
+/// Converts an index tensor to `usize`s, checking each is an integer in `0..bound`.
+fn to_indices(data: &TensorData, bound: usize) -> ArrayD<usize> {
+    data.to_array().mapv(|v| {
+        if v.fract() != 0.0 || v < 0.0 {
+            panic!("Indices must be non-negative integers, got {}", v);
+        }
+        if v as usize >= bound {
+            panic!("Index {} out of range for axis of size {}", v, bound);
+        }
+        v as usize
+    })
+}
+
+fn check_axis(axis: usize, ndim: usize) {
+    assert!(axis < ndim, "Axis {} out of range for a {}-d tensor", axis, ndim);
+}
+
+/// Checks that `indices` fits inside `shape` on every axis except `axis`.
+fn check_index_shape(indices: &ArrayD<usize>, shape: &[usize], axis: Option<usize>) {
+    assert_eq!(indices.ndim(), shape.len(), "Index tensor must have the same number of dimensions as the input");
+    for (d, (&i, &s)) in indices.shape().iter().zip(shape).enumerate() {
+        assert!(Some(d) == axis || i <= s, "Index tensor of shape {:?} does not fit shape {:?}", indices.shape(), shape);
+    }
+}
+
+fn gather_array(x: &ArrayD<f32>, axis: usize, indices: &ArrayD<usize>) -> ArrayD<f32> {
+    let mut out = backend::current().zeros(indices.shape());
+    for (pos, &i) in indices.indexed_iter() {
+        let mut src = pos.clone();
+        src[axis] = i;
+        out[pos] = x[src];
+    }
+    out
+}
+
+fn scatter_add_array(target: &mut ArrayD<f32>, axis: usize, indices: &ArrayD<usize>, src: &ArrayD<f32>) {
+    for (pos, &i) in indices.indexed_iter() {
+        let mut dst = pos.clone();
+        dst[axis] = i;
+        target[dst] += src[pos];
+    }
+}
+
+fn index_add_array(target: &mut ArrayD<f32>, axis: usize, indices: &[usize], src: &ArrayD<f32>) {
+    for (k, &i) in indices.iter().enumerate() {
+        let mut lane = target.index_axis_mut(Axis(axis), i);
+        lane += &src.index_axis(Axis(axis), k);
+    }
+}
+
+fn index_vec(data: &TensorData, bound: usize) -> Vec<usize> {
+    let indices = to_indices(data, bound);
+    assert!(indices.ndim() == 1, "Indices must be a 1-D tensor");
+    indices.into_iter().collect()
+}
+
+impl Op for Gather {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let x = input_array(inputs, 0);
+        check_axis(self.axis, x.ndim());
+        let indices = to_indices(inputs[1].borrow().value(), x.shape()[self.axis]);
+        check_index_shape(&indices, x.shape(), Some(self.axis));
+
+        TensorData::Tensor(gather_array(&x, self.axis, &indices))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let shape = output_borrow.parents[0].borrow().value().shape();
+        let indices = to_indices(output_borrow.parents[1].borrow().value(), shape[self.axis]);
+
+        let mut grad = backend::current().zeros(&shape);
+        scatter_add_array(&mut grad, self.axis, &indices, &grad_output.to_array());
+
+        vec![TensorData::Tensor(grad), TensorData::Tensor(backend::current().zeros(indices.shape()))]
+    }
+
+    fn name(&self) -> &'static str { "Gather" }
+}
+
+impl Op for ScatterAdd {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let mut x = input_array(inputs, 0);
+        let src = input_array(inputs, 2);
+        check_axis(self.axis, x.ndim());
+        let indices = to_indices(inputs[1].borrow().value(), x.shape()[self.axis]);
+        check_index_shape(&indices, x.shape(), Some(self.axis));
+        check_index_shape(&indices, src.shape(), None);
+
+        scatter_add_array(&mut x, self.axis, &indices, &src);
+        TensorData::Tensor(x)
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let shape = output_borrow.parents[0].borrow().value().shape();
+        let src_shape = output_borrow.parents[2].borrow().value().shape();
+        let indices = to_indices(output_borrow.parents[1].borrow().value(), shape[self.axis]);
+
+        // Elements of src outside the index tensor's extent are never scattered.
+        let mut grad_src = backend::current().zeros(&src_shape);
+        let gathered = gather_array(&grad_output.to_array(), self.axis, &indices);
+        grad_src.slice_each_axis_mut(|ax| Slice::from(..indices.shape()[ax.axis.index()])).assign(&gathered);
+
+        vec![
+            grad_output.clone(),
+            TensorData::Tensor(backend::current().zeros(indices.shape())),
+            TensorData::Tensor(grad_src)
+        ]
+    }
+
+    fn name(&self) -> &'static str { "ScatterAdd" }
+}
+
+impl Op for IndexSelect {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let x = input_array(inputs, 0);
+        check_axis(self.axis, x.ndim());
+        let indices = index_vec(inputs[1].borrow().value(), x.shape()[self.axis]);
+
+        TensorData::Tensor(x.select(Axis(self.axis), &indices))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let shape = output_borrow.parents[0].borrow().value().shape();
+        let indices = index_vec(output_borrow.parents[1].borrow().value(), shape[self.axis]);
+
+        let mut grad = backend::current().zeros(&shape);
+        index_add_array(&mut grad, self.axis, &indices, &grad_output.to_array());
+
+        vec![TensorData::Tensor(grad), TensorData::Tensor(backend::current().zeros(&[indices.len()]))]
+    }
+
+    fn name(&self) -> &'static str { "IndexSelect" }
+}
+
+impl Op for IndexAdd {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let mut x = input_array(inputs, 0);
+        let src = input_array(inputs, 2);
+        check_axis(self.axis, x.ndim());
+        let indices = index_vec(inputs[1].borrow().value(), x.shape()[self.axis]);
+
+        let mut expected = x.shape().to_vec();
+        expected[self.axis] = indices.len();
+        assert_eq!(src.shape(), &expected[..], "index_add source has the wrong shape");
+
+        index_add_array(&mut x, self.axis, &indices, &src);
+        TensorData::Tensor(x)
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let shape = output_borrow.parents[0].borrow().value().shape();
+        let indices = index_vec(output_borrow.parents[1].borrow().value(), shape[self.axis]);
+
+        let grad_src = grad_output.to_array().select(Axis(self.axis), &indices);
+
+        vec![
+            grad_output.clone(),
+            TensorData::Tensor(backend::current().zeros(&[indices.len()])),
+            TensorData::Tensor(grad_src)
+        ]
+    }
+
+    fn name(&self) -> &'static str { "IndexAdd" }
+}
+
+fn apply_indexing_op(a: &TensorRef, op: Rc<dyn Op>) -> TensorRef {
+    Tensor::from_op(op, &[a])
+}
+
+/// Slices `a` with one `ndarray::Slice` per leading axis, e.g.
+/// `slice(&x, &[Slice::from(1..), Slice::new(0, None, 2)])`.
+pub fn slice(a: &TensorRef, ranges: &[Slice]) -> TensorRef {
+    apply_indexing_op(a, Rc::new(SliceOp {ranges: ranges.to_vec()}))
+}
+
+/// Selects position `index` along `axis`, removing that axis.
+pub fn index(a: &TensorRef, axis: usize, index: usize) -> TensorRef {
+    apply_indexing_op(a, Rc::new(Index {axis, index}))
+}
+
+/// Takes `length` elements along `axis`, starting at `start`.
+pub fn narrow(a: &TensorRef, axis: usize, start: usize, length: usize) -> TensorRef {
+    let end = start.checked_add(length).filter(|&end| end <= isize::MAX as usize);
+    let end = end.unwrap_or_else(|| panic!("narrow of length {} starting at {} overflows", length, start));
+    let mut ranges = vec![Slice::from(..); axis + 1];
+    ranges[axis] = Slice::from(start..end);
+    apply_indexing_op(a, Rc::new(SliceOp {ranges}))
+}
+
+/// `out[..., i, ...] = a[..., indices[..., i, ...], ...]` along `axis`.
+pub fn gather(a: &TensorRef, axis: usize, indices: &TensorRef) -> TensorRef {
+    Tensor::from_op(Rc::new(Gather {axis}), &[a, indices])
+}
+
+/// Copy of `a` with each element of `src` added at the position `indices` gives along `axis`.
+pub fn scatter_add(a: &TensorRef, axis: usize, indices: &TensorRef, src: &TensorRef) -> TensorRef {
+    Tensor::from_op(Rc::new(ScatterAdd {axis}), &[a, indices, src])
+}
+
+/// Selects the entries listed in the 1-D `indices` along `axis`.
+pub fn index_select(a: &TensorRef, axis: usize, indices: &TensorRef) -> TensorRef {
+    Tensor::from_op(Rc::new(IndexSelect {axis}), &[a, indices])
+}
+
+/// Copy of `a` with slice `k` of `src` along `axis` added at position `indices[k]`.
+pub fn index_add(a: &TensorRef, axis: usize, indices: &TensorRef, src: &TensorRef) -> TensorRef {
+    Tensor::from_op(Rc::new(IndexAdd {axis}), &[a, indices, src])
+}
@@ -0,0 +1,248 @@
+use crate::backend::{self, broadcast_shape};
+use crate::tensor::*;
+use crate::ops::op_defs::{Op, MatMul, Einsum};
+use crate::ops::shape_ops::sum_to_shape;
+use ndarray::{ArrayD, Axis, IxDyn};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+fn swap_last(arr: &ArrayD<f32>) -> ArrayD<f32> {
+    let mut view = arr.view();
+    view.swap_axes(arr.ndim() - 2, arr.ndim() - 1);
+    view.to_owned()
+}
+
+/// `[..., M, K] x [..., K, N] -> [..., M, N]`, broadcasting the batch dims.
+/// Operands without batch dims go straight to the backend's 2-D kernel.
+fn batched_matmul(a: &ArrayD<f32>, b: &ArrayD<f32>) -> ArrayD<f32> {
+    let backend = backend::current();
+    let (a_batch, a_mat) = a.shape().split_at(a.ndim() - 2);
+    let (b_batch, b_mat) = b.shape().split_at(b.ndim() - 2);
+    let (m, k, n) = (a_mat[0], a_mat[1], b_mat[1]);
+    assert_eq!(
+        k, b_mat[0],
+        "matmul shapes {:?} and {:?} have mismatched inner dimensions", a.shape(), b.shape()
+    );
+
+    if a_batch.is_empty() && b_batch.is_empty() {
+        return backend.matmul(a, b);
+    }
+
+    let batch = broadcast_shape(a_batch, b_batch)
+        .unwrap_or_else(|| panic!("matmul cannot broadcast batch dims of {:?} and {:?}", a.shape(), b.shape()));
+    let count: usize = batch.iter().product();
+
+    let a3 = backend.broadcast(a, &[&batch[..], &[m, k]].concat()).into_shape_clone(IxDyn(&[count, m, k])).unwrap();
+    let b3 = backend.broadcast(b, &[&batch[..], &[k, n]].concat()).into_shape_clone(IxDyn(&[count, k, n])).unwrap();
+    backend.batched_matmul(&a3, &b3).into_shape_clone(IxDyn(&[&batch[..], &[m, n]].concat())).unwrap()
+}
+
+/// Promotes 1-D operands to matrices (`[K] -> [1, K]` on the left,
+/// `[K] -> [K, 1]` on the right), as in numpy's `matmul`.
+fn promote(a: &ArrayD<f32>, b: &ArrayD<f32>) -> (ArrayD<f32>, ArrayD<f32>) {
+    assert!(a.ndim() >= 1 && b.ndim() >= 1, "matmul does not accept scalars");
+    let a = if a.ndim() == 1 { a.clone().insert_axis(Axis(0)) } else { a.clone() };
+    let b = if b.ndim() == 1 { b.clone().insert_axis(Axis(1)) } else { b.clone() };
+    (a, b)
+}
+
+impl Op for MatMul {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let a = inputs[0].borrow().value().to_array();
+        let b = inputs[1].borrow().value().to_array();
+        let (pa, pb) = promote(&a, &b);
+
+        let mut out = batched_matmul(&pa, &pb);
+        if b.ndim() == 1 {
+            let ax = out.ndim() - 1;
+            out = out.remove_axis(Axis(ax));
+        }
+        if a.ndim() == 1 {
+            let ax = out.ndim() - if b.ndim() == 1 { 1 } else { 2 };
+            out = out.remove_axis(Axis(ax));
+        }
+        TensorData::from_array(out)
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let a = output_borrow.parents[0].borrow().value().to_array();
+        let b = output_borrow.parents[1].borrow().value().to_array();
+        let (pa, pb) = promote(&a, &b);
+
+        // Restore the axes removed for 1-D operands so grad is [..., M, N].
+        let mut grad = grad_output.to_array();
+        if a.ndim() == 1 {
+            let ax = grad.ndim() - if b.ndim() == 1 { 0 } else { 1 };
+            grad = grad.insert_axis(Axis(ax));
+        }
+        if b.ndim() == 1 {
+            let ax = grad.ndim();
+            grad = grad.insert_axis(Axis(ax));
+        }
+
+        // dL/dA = dL/dC @ B^T, dL/dB = A^T @ dL/dC, summed over broadcast batch dims.
+        let grad_a = sum_to_shape(&TensorData::Tensor(batched_matmul(&grad, &swap_last(&pb))), pa.shape());
+        let grad_b = sum_to_shape(&TensorData::Tensor(batched_matmul(&swap_last(&pa), &grad)), pb.shape());
+
+        vec![
+            TensorData::Tensor(grad_a.to_array().into_shape_clone(IxDyn(a.shape())).unwrap()),
+            TensorData::Tensor(grad_b.to_array().into_shape_clone(IxDyn(b.shape())).unwrap())
+        ]
+    }
+
+    fn name(&self) -> &'static str { "MatMul" }
+}
+
+impl Einsum {
+    /// Parses `"ij,jk->ik"`. Without `->`, the output is every letter that
+    /// appears exactly once, in alphabetical order.
+    pub fn parse(equation: &str) -> Einsum {
+        let equation: String = equation.chars().filter(|c| !c.is_whitespace()).collect();
+        let (lhs, rhs) = match equation.split_once("->") {
+            Some((lhs, rhs)) => (lhs, Some(rhs)),
+            None => (equation.as_str(), None)
+        };
+
+        let inputs: Vec<Vec<char>> = lhs.split(',').map(|sub| sub.chars().collect()).collect();
+        for c in inputs.iter().flatten() {
+            assert!(c.is_ascii_alphabetic(), "Invalid subscript {:?} in einsum equation {:?}", c, equation);
+        }
+
+        let output: Vec<char> = match rhs {
+            Some(rhs) => rhs.chars().collect(),
+            None => {
+                let mut once: Vec<char> = inputs.iter().flatten()
+                    .filter(|&&c| inputs.iter().flatten().filter(|&&d| d == c).count() == 1)
+                    .copied()
+                    .collect();
+                once.sort();
+                once
+            }
+        };
+        for (i, c) in output.iter().enumerate() {
+            assert!(
+                inputs.iter().flatten().any(|d| d == c),
+                "Output subscript {:?} does not appear in the inputs of {:?}", c, equation
+            );
+            assert!(!output[..i].contains(c), "Output subscript {:?} repeated in {:?}", c, equation);
+        }
+
+        Einsum {inputs, output}
+    }
+}
+
+/// Sizes of every subscript letter, checking that repeated letters agree.
+fn letter_sizes(subscripts: &[&[char]], arrays: &[&ArrayD<f32>]) -> HashMap<char, usize> {
+    let mut sizes = HashMap::new();
+    for (sub, arr) in subscripts.iter().zip(arrays) {
+        assert_eq!(
+            sub.len(), arr.ndim(),
+            "einsum subscript {:?} does not match operand of shape {:?}", sub.iter().collect::<String>(), arr.shape()
+        );
+        for (&c, &dim) in sub.iter().zip(arr.shape()) {
+            let size = *sizes.entry(c).or_insert(dim);
+            assert_eq!(size, dim, "einsum subscript {:?} has inconsistent sizes {} and {}", c, size, dim);
+        }
+    }
+    sizes
+}
+
+/// `out[output] = sum over all other letters of prod_i operands[i][subscripts[i]]`.
+/// Letters may repeat (diagonals) and output letters may be absent from the
+/// operands (broadcast), which the backward passes rely on.
+fn contract(subscripts: &[&[char]], arrays: &[&ArrayD<f32>], output: &[char], sizes: &HashMap<char, usize>) -> ArrayD<f32> {
+    let mut letters: Vec<char> = subscripts.iter().flat_map(|s| s.iter()).chain(output).copied().collect();
+    letters.sort();
+    letters.dedup();
+    let dims: Vec<usize> = letters.iter().map(|c| sizes[c]).collect();
+    let position = |c: &char| letters.iter().position(|l| l == c).unwrap();
+
+    // Flat offset of an operand element, as (letter position, stride) pairs.
+    let offsets = |sub: &[char]| -> Vec<(usize, usize)> {
+        let mut stride = 1;
+        let mut pairs: Vec<(usize, usize)> = sub.iter().rev().map(|c| {
+            let pair = (position(c), stride);
+            stride *= sizes[c];
+            pair
+        }).collect();
+        pairs.reverse();
+        pairs
+    };
+    let operand_offsets: Vec<Vec<(usize, usize)>> = subscripts.iter().map(|s| offsets(s)).collect();
+    let output_offsets = offsets(output);
+    let data: Vec<Vec<f32>> = arrays.iter().map(|a| a.iter().copied().collect()).collect();
+
+    let out_shape: Vec<usize> = output.iter().map(|c| sizes[c]).collect();
+    let mut out = vec![0.0; out_shape.iter().product()];
+    let mut index = vec![0; letters.len()];
+    let flat = |pairs: &[(usize, usize)], index: &[usize]| -> usize {
+        pairs.iter().map(|&(p, stride)| index[p] * stride).sum()
+    };
+
+    for _ in 0..dims.iter().product::<usize>() {
+        let product: f32 = operand_offsets.iter().zip(&data).map(|(pairs, d)| d[flat(pairs, &index)]).product();
+        out[flat(&output_offsets, &index)] += product;
+
+        for d in (0..letters.len()).rev() {
+            index[d] += 1;
+            if index[d] < dims[d] {
+                break;
+            }
+            index[d] = 0;
+        }
+    }
+
+    backend::current().array(&out_shape, out)
+}
+
+impl Op for Einsum {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        assert_eq!(
+            inputs.len(), self.inputs.len(),
+            "einsum equation expects {} operands, got {}", self.inputs.len(), inputs.len()
+        );
+        let arrays: Vec<ArrayD<f32>> = inputs.iter().map(|t| t.borrow().value().to_array()).collect();
+        let arrays: Vec<&ArrayD<f32>> = arrays.iter().collect();
+        let subscripts: Vec<&[char]> = self.inputs.iter().map(|s| s.as_slice()).collect();
+
+        let sizes = letter_sizes(&subscripts, &arrays);
+        TensorData::from_array(contract(&subscripts, &arrays, &self.output, &sizes))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let arrays: Vec<ArrayD<f32>> = output_borrow.parents.iter().map(|t| t.borrow().value().to_array()).collect();
+        let arrays: Vec<&ArrayD<f32>> = arrays.iter().collect();
+        let subscripts: Vec<&[char]> = self.inputs.iter().map(|s| s.as_slice()).collect();
+        let sizes = letter_sizes(&subscripts, &arrays);
+        let grad = grad_output.to_array();
+
+        // The gradient of operand i is the einsum of the output gradient with
+        // every other operand, producing operand i's subscripts.
+        (0..arrays.len()).map(|i| {
+            let mut subs: Vec<&[char]> = vec![&self.output];
+            let mut operands: Vec<&ArrayD<f32>> = vec![&grad];
+            for j in (0..arrays.len()).filter(|&j| j != i) {
+                subs.push(subscripts[j]);
+                operands.push(arrays[j]);
+            }
+            TensorData::from_array(contract(&subs, &operands, subscripts[i], &sizes))
+        }).collect()
+    }
+
+    fn name(&self) -> &'static str { "Einsum" }
+}
+
+/// Matrix product with numpy semantics: vector-vector (dot), matrix-vector,
+/// matrix-matrix and batched `[..., M, K] x [..., K, N]` with broadcast batch dims.
+pub fn matmul(a: &TensorRef, b: &TensorRef) -> TensorRef {
+    Tensor::from_op(Rc::new(MatMul), &[a, b])
+}
+
+/// Einstein summation, e.g. `einsum("bij,bjk->bik", &[a, b])` for a batched
+/// matrix product or `einsum("i,j->ij", &[u, v])` for an outer product.
+pub fn einsum(equation: &str, operands: &[TensorRef]) -> TensorRef {
+    let inputs: Vec<&TensorRef> = operands.iter().collect();
+    Tensor::from_op(Rc::new(Einsum::parse(equation)), &inputs)
+}
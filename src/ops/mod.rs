@@ -1,4 +1,5 @@
 pub mod op_defs;
+pub use op_defs::{ConvOptions, PadMode, PoolOptions};
 
 pub mod unary_ops;
 pub use unary_ops::*;
@@ -7,4 +8,34 @@ pub mod binary_ops;
 pub use binary_ops::*;
 
 pub mod reduction_ops;
-pub use reduction_ops::*;
\ No newline at end of file
+pub use reduction_ops::*;
+
+pub mod indexing_ops;
+pub use indexing_ops::*;
+
+pub mod shape_ops;
+pub use shape_ops::*;
+
+pub mod concat_ops;
+pub use concat_ops::*;
+
+pub mod linalg_ops;
+pub use linalg_ops::*;
+
+pub mod conv_ops;
+pub use conv_ops::*;
+
+pub mod pool_ops;
+pub use pool_ops::*;
+
+pub mod softmax_ops;
+pub use softmax_ops::*;
+
+pub mod cumulative_ops;
+pub use cumulative_ops::*;
+
+pub mod comparison_ops;
+pub use comparison_ops::*;
+
+pub mod sort_ops;
+pub use sort_ops::*;
@@ -1,10 +1,29 @@
 use crate::tensor::*;
+use ndarray::Slice;
+use std::cell::RefCell;
 use std::fmt::Debug;
+use std::rc::Rc;
 
 pub trait Op: Debug {
     fn forward(&self, inputs: &[&TensorRef]) -> TensorData;
     fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData>;
     fn name(&self) -> &'static str { "PrimitiveOp "}
+
+    /// For elementwise unary ops, the function applied to each element. Lazy
+    /// realization uses it to compute in place in an input nothing else reads.
+    fn elementwise(&self) -> Option<fn(f32) -> f32> { None }
+
+    /// For the outputs of a `MultiOp`: the shared node and this output's position in it.
+    fn multi_output(&self) -> Option<(&Rc<MultiNode>, usize)> { None }
+}
+
+/// An op producing several outputs that share one backward pass.
+pub trait MultiOp: Debug {
+    fn num_outputs(&self) -> usize;
+    fn forward(&self, inputs: &[&TensorRef]) -> Vec<TensorData>;
+    /// `grad_outputs[i]` is `None` when output `i` received no gradient.
+    fn backward(&self, inputs: &[TensorRef], grad_outputs: &[Option<TensorData>]) -> Vec<TensorData>;
+    fn name(&self) -> &'static str { "PrimitiveMultiOp" }
 }
 
 // Unary Ops
@@ -18,6 +37,89 @@ pub struct Abs;
 #[derive(Debug)]
 pub struct ReLU;
 
+#[derive(Debug)]
+pub struct Exp;
+
+#[derive(Debug)]
+pub struct Log;
+
+#[derive(Debug)]
+pub struct Log1p;
+
+#[derive(Debug)]
+pub struct Sqrt;
+
+#[derive(Debug)]
+pub struct Rsqrt;
+
+#[derive(Debug)]
+pub struct Sin;
+
+#[derive(Debug)]
+pub struct Cos;
+
+#[derive(Debug)]
+pub struct Tan;
+
+#[derive(Debug)]
+pub struct Tanh;
+
+#[derive(Debug)]
+pub struct Sigmoid;
+
+#[derive(Debug)]
+pub struct Reciprocal;
+
+#[derive(Debug)]
+pub struct Square;
+
+#[derive(Debug)]
+pub struct LeakyReLU {
+    pub slope: f32
+}
+
+#[derive(Debug)]
+pub struct ELU {
+    pub alpha: f32
+}
+
+#[derive(Debug)]
+pub struct SELU;
+
+#[derive(Debug)]
+pub struct GELU {
+    /// Use the tanh approximation instead of the exact erf form.
+    pub approximate: bool
+}
+
+#[derive(Debug)]
+pub struct SiLU;
+
+#[derive(Debug)]
+pub struct Mish;
+
+/// `log(1 + exp(beta * x)) / beta`, linear once `beta * x > threshold`.
+#[derive(Debug)]
+pub struct Softplus {
+    pub beta: f32,
+    pub threshold: f32
+}
+
+#[derive(Debug)]
+pub struct Hardtanh {
+    pub min: f32,
+    pub max: f32
+}
+
+#[derive(Debug)]
+pub struct Hardswish;
+
+/// `x ^ exponent` for a constant exponent.
+#[derive(Debug)]
+pub struct PowScalar {
+    pub exponent: f32
+}
+
 // Binary Ops
 
 #[derive(Debug)]
@@ -32,16 +134,345 @@ pub struct Sub;
 #[derive(Debug)]
 pub struct Div;
 
+#[derive(Debug)]
+pub struct Pow;
+
+/// Elementwise max; on ties the gradient goes to `b`, so `maximum(x, 0)` matches `ReLU`.
+#[derive(Debug)]
+pub struct Maximum;
+
+/// Elementwise min; on ties the gradient goes to `b`.
+#[derive(Debug)]
+pub struct Minimum;
+
+// Comparison Ops
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CompareKind {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge
+}
+
+/// Elementwise comparison giving 1.0 where it holds and 0.0 elsewhere. Not differentiable.
+#[derive(Debug)]
+pub struct Compare {
+    pub kind: CompareKind
+}
+
+/// Inputs: `[cond, a, b]`, broadcast together; picks `a` where `cond` is non-zero.
+#[derive(Debug)]
+pub struct Where;
+
+/// Inputs: `[x, mask]`; `mask` broadcasts to `x`'s shape.
+#[derive(Debug)]
+pub struct MaskedFill {
+    pub value: f32
+}
+
 // Reduction Ops
 
+// Reductions take signed axes (`-1` is the last axis); `None` reduces over all of them.
+
 #[derive(Debug)]
 pub struct Sum {
-    pub axes: Option<Vec<usize>>,
+    pub axes: Option<Vec<isize>>,
     pub keepdims: bool,
 }
 
 #[derive(Debug)]
 pub struct Mean {
-    pub axes: Option<Vec<usize>>,
+    pub axes: Option<Vec<isize>>,
+    pub keepdims: bool
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReduceKind {
+    /// Gradient is split evenly between tied maxima.
+    Max,
+    Min,
+    Prod,
+    /// Divides by `n - 1` when `unbiased`, else by `n`.
+    Var { unbiased: bool },
+    Std { unbiased: bool },
+    LogSumExp,
+    /// `(sum |x|^p)^(1/p)`; `p = f32::INFINITY` gives the max norm.
+    Norm { p: f32 }
+}
+
+/// Reductions beyond `Sum`/`Mean`, with the same `axes`/`keepdims` conventions.
+#[derive(Debug)]
+pub struct Reduce {
+    pub kind: ReduceKind,
+    pub axes: Option<Vec<isize>>,
+    pub keepdims: bool
+}
+
+/// Index of the first maximum (`largest`) or minimum along `axis`, or into
+/// the flattened tensor when `axis` is `None`. Not differentiable.
+#[derive(Debug)]
+pub struct ArgReduce {
+    pub largest: bool,
+    pub axis: Option<isize>,
     pub keepdims: bool
-}
\ No newline at end of file
+}
+
+// Cumulative Ops
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CumulativeKind {
+    Sum,
+    Prod,
+    /// Gradient goes to the most recent position holding the running max.
+    Max,
+    Min,
+    LogSumExp
+}
+
+/// Inclusive scan along `axis` (signed, like the reduction axes).
+#[derive(Debug)]
+pub struct Cumulative {
+    pub kind: CumulativeKind,
+    pub axis: isize
+}
+
+// Sorting Ops
+
+/// Outputs `[values, indices]` of a stable sort along `axis`, keeping the
+/// first `k` entries of each lane when `k` is set. The permutation of each
+/// lane is saved in `permutation` during forward for the backward pass.
+#[derive(Debug)]
+pub struct Sort {
+    pub axis: isize,
+    pub descending: bool,
+    pub k: Option<usize>,
+    pub permutation: RefCell<Vec<usize>>
+}
+
+// Softmax Ops
+
+#[derive(Debug)]
+pub struct Softmax {
+    pub axis: isize
+}
+
+#[derive(Debug)]
+pub struct LogSoftmax {
+    pub axis: isize
+}
+
+// Indexing Ops
+
+/// Per-axis `ndarray::Slice`s; axes without an entry are taken whole.
+#[derive(Debug)]
+pub struct SliceOp {
+    pub ranges: Vec<Slice>
+}
+
+#[derive(Debug)]
+pub struct Index {
+    pub axis: usize,
+    pub index: usize
+}
+
+/// Indices are passed as the second input, an integer-valued tensor.
+#[derive(Debug)]
+pub struct Gather {
+    pub axis: usize
+}
+
+/// Inputs: `[x, indices, src]`.
+#[derive(Debug)]
+pub struct ScatterAdd {
+    pub axis: usize
+}
+
+/// Indices are passed as the second input, a 1-D integer-valued tensor.
+#[derive(Debug)]
+pub struct IndexSelect {
+    pub axis: usize
+}
+
+/// Inputs: `[x, indices, src]`.
+#[derive(Debug)]
+pub struct IndexAdd {
+    pub axis: usize
+}
+
+// Shape Ops
+
+/// Target shape; at most one entry may be `-1`, inferred from the element count.
+#[derive(Debug)]
+pub struct Reshape {
+    pub shape: Vec<isize>
+}
+
+/// Merges axes `start..=end` into one.
+#[derive(Debug)]
+pub struct Flatten {
+    pub start: usize,
+    pub end: usize
+}
+
+#[derive(Debug)]
+pub struct Squeeze {
+    pub axis: usize
+}
+
+#[derive(Debug)]
+pub struct Unsqueeze {
+    pub axis: usize
+}
+
+/// Broadcast target shape; `-1` keeps the input's size on that axis.
+#[derive(Debug)]
+pub struct Expand {
+    pub shape: Vec<isize>
+}
+
+/// Output axis `i` is input axis `order[i]`.
+#[derive(Debug)]
+pub struct Permute {
+    pub order: Vec<usize>
+}
+
+/// Swaps two axes; `None` swaps the last two (and is a no-op below 2-D).
+#[derive(Debug)]
+pub struct Transpose {
+    pub axes: Option<(usize, usize)>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PadMode {
+    Constant(f32),
+    /// Mirrors around the edge without repeating it; pads must be smaller than the axis.
+    Reflect,
+    /// Repeats the edge value.
+    Replicate,
+    /// Wraps around; pads can be at most the axis size.
+    Circular
+}
+
+/// `pads[i]` is the `(before, after)` padding of axis `ndim - pads.len() + i`.
+#[derive(Debug)]
+pub struct Pad {
+    pub pads: Vec<(usize, usize)>,
+    pub mode: PadMode
+}
+
+// Multi-input / Multi-output Ops
+
+#[derive(Debug)]
+pub struct Cat {
+    pub axis: usize
+}
+
+#[derive(Debug)]
+pub struct Stack {
+    pub axis: usize
+}
+
+/// Splits along `axis` into consecutive pieces of the given sizes.
+#[derive(Debug)]
+pub struct Split {
+    pub sizes: Vec<usize>,
+    pub axis: usize
+}
+
+/// Splits along `axis` into `chunks` pieces whose sizes differ by at most one.
+#[derive(Debug)]
+pub struct Chunk {
+    pub chunks: usize,
+    pub axis: usize
+}
+
+// Linear Algebra Ops
+
+#[derive(Debug)]
+pub struct MatMul;
+
+/// Parsed `einsum` equation: one subscript per input and the output subscript.
+#[derive(Debug)]
+pub struct Einsum {
+    pub inputs: Vec<Vec<char>>,
+    pub output: Vec<char>
+}
+
+// Convolution Ops
+
+/// Hyperparameters shared by every spatial dimension of a convolution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvOptions {
+    pub stride: usize,
+    pub padding: usize,
+    pub dilation: usize,
+    pub groups: usize
+}
+
+impl Default for ConvOptions {
+    fn default() -> ConvOptions {
+        ConvOptions { stride: 1, padding: 0, dilation: 1, groups: 1 }
+    }
+}
+
+/// Convolution over `spatial_dims` trailing dims of an `[N, C, ...]` input.
+/// Inputs: `[x, weight]` or `[x, weight, bias]`.
+#[derive(Debug)]
+pub struct Conv {
+    pub spatial_dims: usize,
+    pub options: ConvOptions
+}
+
+/// Transposed convolution (the adjoint of `Conv`), with weight `[C_in, C_out / groups, ...]`.
+/// Inputs: `[x, weight]` or `[x, weight, bias]`.
+#[derive(Debug)]
+pub struct ConvTranspose {
+    pub spatial_dims: usize,
+    pub options: ConvOptions,
+    pub output_padding: usize
+}
+
+// Pooling Ops
+
+/// Window hyperparameters shared by every spatial dimension of a pooling op.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolOptions {
+    pub kernel: usize,
+    pub stride: usize,
+    pub padding: usize,
+    pub ceil_mode: bool
+}
+
+impl PoolOptions {
+    /// Non-overlapping windows of size `kernel`, without padding.
+    pub fn new(kernel: usize) -> PoolOptions {
+        PoolOptions { kernel, stride: kernel, padding: 0, ceil_mode: false }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PoolKind {
+    Max,
+    Avg
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PoolWindow {
+    Fixed(PoolOptions),
+    /// Windows chosen so the output has exactly this spatial shape.
+    Adaptive(Vec<usize>)
+}
+
+/// Pooling over `spatial_dims` trailing dims of an `[N, C, ...]` input.
+/// Max pooling records the flat input position of each window's maximum in
+/// `argmax` during forward, for routing gradients in backward.
+#[derive(Debug)]
+pub struct Pool {
+    pub spatial_dims: usize,
+    pub kind: PoolKind,
+    pub window: PoolWindow,
+    pub argmax: RefCell<Vec<usize>>
+}
@@ -0,0 +1,182 @@
+use crate::backend;
+use crate::tensor::*;
+use crate::ops::op_defs::{Op, Pool, PoolKind, PoolOptions, PoolWindow};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// `(start, end, divisor)` of each window along one dim; `start..end` is
+/// clipped to the input, while `divisor` also counts padded positions.
+fn dim_windows(len: usize, window: &PoolWindow, dim: usize) -> Vec<(usize, usize, usize)> {
+    match window {
+        PoolWindow::Fixed(PoolOptions { kernel, stride, padding, ceil_mode }) => {
+            let (k, s, p) = (*kernel, *stride, *padding);
+            assert!(k > 0 && s > 0, "Pooling kernel and stride must be positive");
+            assert!(p <= k / 2, "Padding {} should be at most half of kernel size {}", p, k);
+            assert!(len + 2 * p >= k, "Kernel of size {} does not fit input of size {} with padding {}", k, len, p);
+
+            let span = len + 2 * p - k;
+            let mut out = if *ceil_mode { span.div_ceil(s) + 1 } else { span / s + 1 };
+            // The last window must start inside the input or its left padding.
+            if *ceil_mode && (out - 1) * s >= len + p {
+                out -= 1;
+            }
+
+            (0..out).map(|i| {
+                let start = (i * s) as isize - p as isize;
+                let end = (start + k as isize).min((len + p) as isize);
+                (start.max(0) as usize, (end as usize).min(len), (end - start) as usize)
+            }).collect()
+        },
+        PoolWindow::Adaptive(shape) => {
+            let out = shape[dim];
+            assert!(out > 0, "Adaptive pooling output size must be positive");
+            (0..out).map(|i| {
+                let (start, end) = (i * len / out, ((i + 1) * len).div_ceil(out));
+                (start, end, end - start)
+            }).collect()
+        }
+    }
+}
+
+/// The flat input positions and divisor of every output position.
+struct PoolGeometry {
+    output: Vec<usize>,
+    windows: Vec<(Vec<usize>, f32)>
+}
+
+impl PoolGeometry {
+    fn new(input: &[usize], window: &PoolWindow) -> PoolGeometry {
+        if let PoolWindow::Adaptive(shape) = window {
+            assert_eq!(shape.len(), input.len(), "Adaptive pooling expects {} output sizes, got {:?}", input.len(), shape);
+        }
+
+        let mut windows = vec![(vec![0], 1.0)];
+        let mut output = Vec::with_capacity(input.len());
+        for (dim, &len) in input.iter().enumerate() {
+            let dim_windows = dim_windows(len, window, dim);
+            output.push(dim_windows.len());
+            windows = windows.iter().flat_map(|(positions, divisor)| {
+                dim_windows.iter().map(move |&(start, end, d)| {
+                    let positions = positions.iter().flat_map(|&p| (start..end).map(move |j| p * len + j)).collect();
+                    (positions, divisor * d as f32)
+                })
+            }).collect();
+        }
+
+        PoolGeometry { output, windows }
+    }
+}
+
+fn pool_shapes(x: &[usize], spatial_dims: usize) -> (usize, Vec<usize>) {
+    assert_eq!(
+        x.len(), spatial_dims + 2,
+        "pool{}d expects an input of shape [N, C, ...] with {} spatial dims, got {:?}", spatial_dims, spatial_dims, x
+    );
+    (x[0] * x[1], x[2..].to_vec())
+}
+
+impl Op for Pool {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let x = inputs[0].borrow().value().to_array();
+        let (planes, spatial) = pool_shapes(x.shape(), self.spatial_dims);
+        let geo = PoolGeometry::new(&spatial, &self.window);
+        let input_len: usize = spatial.iter().product();
+        let data: Vec<f32> = x.iter().copied().collect();
+
+        let mut out = Vec::with_capacity(planes * geo.windows.len());
+        let mut argmax = Vec::new();
+        for p in 0..planes {
+            let plane = &data[p * input_len..(p + 1) * input_len];
+            for (positions, divisor) in &geo.windows {
+                match self.kind {
+                    PoolKind::Max => {
+                        // The first NaN in a window wins, as in `max`.
+                        let best = positions.iter().copied()
+                            .reduce(|best, i| {
+                                let better = !plane[best].is_nan() && (plane[i].is_nan() || plane[i] > plane[best]);
+                                if better { i } else { best }
+                            })
+                            .expect("Pooling window is empty");
+                        argmax.push(p * input_len + best);
+                        out.push(plane[best]);
+                    },
+                    PoolKind::Avg => out.push(positions.iter().map(|&i| plane[i]).sum::<f32>() / divisor)
+                }
+            }
+        }
+        *self.argmax.borrow_mut() = argmax;
+
+        let mut shape = x.shape()[..2].to_vec();
+        shape.extend(&geo.output);
+        TensorData::Tensor(backend::current().array(&shape, out))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let shape = output.borrow().parents[0].borrow().value().shape();
+        let grad: Vec<f32> = grad_output.to_array().iter().copied().collect();
+        let mut grad_x = vec![0.0; shape.iter().product()];
+
+        match self.kind {
+            PoolKind::Max => {
+                for (&i, g) in self.argmax.borrow().iter().zip(&grad) {
+                    grad_x[i] += g;
+                }
+            },
+            PoolKind::Avg => {
+                let (_, spatial) = pool_shapes(&shape, self.spatial_dims);
+                let geo = PoolGeometry::new(&spatial, &self.window);
+                let input_len: usize = spatial.iter().product();
+                for (j, g) in grad.iter().enumerate() {
+                    let offset = j / geo.windows.len() * input_len;
+                    let (positions, divisor) = &geo.windows[j % geo.windows.len()];
+                    for &i in positions {
+                        grad_x[offset + i] += g / divisor;
+                    }
+                }
+            }
+        }
+
+        vec![TensorData::Tensor(backend::current().array(&shape, grad_x))]
+    }
+
+    fn name(&self) -> &'static str {
+        match self.kind {
+            PoolKind::Max => "MaxPool",
+            PoolKind::Avg => "AvgPool"
+        }
+    }
+}
+
+fn apply_pool_op(x: &TensorRef, spatial_dims: usize, kind: PoolKind, window: PoolWindow) -> TensorRef {
+    Tensor::from_op(Rc::new(Pool {spatial_dims, kind, window, argmax: RefCell::new(Vec::new())}), &[x])
+}
+
+/// Max over windows of `x: [N, C, L]`; padded positions never win.
+pub fn max_pool1d(x: &TensorRef, options: PoolOptions) -> TensorRef {
+    apply_pool_op(x, 1, PoolKind::Max, PoolWindow::Fixed(options))
+}
+
+/// Max over windows of `x: [N, C, H, W]`; padded positions never win.
+pub fn max_pool2d(x: &TensorRef, options: PoolOptions) -> TensorRef {
+    apply_pool_op(x, 2, PoolKind::Max, PoolWindow::Fixed(options))
+}
+
+/// Mean over windows of `x: [N, C, L]`, counting padded positions as zeros.
+pub fn avg_pool1d(x: &TensorRef, options: PoolOptions) -> TensorRef {
+    apply_pool_op(x, 1, PoolKind::Avg, PoolWindow::Fixed(options))
+}
+
+/// Mean over windows of `x: [N, C, H, W]`, counting padded positions as zeros.
+pub fn avg_pool2d(x: &TensorRef, options: PoolOptions) -> TensorRef {
+    apply_pool_op(x, 2, PoolKind::Avg, PoolWindow::Fixed(options))
+}
+
+/// Mean pooling of `x: [N, C, H, W]` down to `[N, C, output[0], output[1]]`.
+pub fn adaptive_avg_pool2d(x: &TensorRef, output: [usize; 2]) -> TensorRef {
+    apply_pool_op(x, 2, PoolKind::Avg, PoolWindow::Adaptive(output.to_vec()))
+}
+
+/// Max pooling of `x: [N, C, H, W]` down to `[N, C, output[0], output[1]]`.
+pub fn adaptive_max_pool2d(x: &TensorRef, output: [usize; 2]) -> TensorRef {
+    apply_pool_op(x, 2, PoolKind::Max, PoolWindow::Adaptive(output.to_vec()))
+}
@@ -1,80 +1,74 @@
+use crate::backend;
 use crate::tensor::*;
-use crate::ops::op_defs::{Op, Sum, Mean};
+use crate::ops::op_defs::{Op, Sum, Mean, Reduce, ReduceKind, ArgReduce};
+use crate::ops::shape_ops::permute_array;
 use ndarray::{ArrayD, Axis, IxDyn};
 use std::rc::Rc;
 
+/// Resolves negative axes, then sorts and deduplicates them; `None` means every axis.
+pub(crate) fn normalize_axes(axes: &Option<Vec<isize>>, ndim: usize) -> Vec<usize> {
+    let Some(axes) = axes else {
+        return (0..ndim).collect();
+    };
+
+    let mut normalized: Vec<usize> = axes.iter().map(|&ax| {
+        let resolved = if ax < 0 { ax + ndim as isize } else { ax };
+        assert!(
+            (0..ndim as isize).contains(&resolved),
+            "Axis {} out of range for a {}-d tensor", ax, ndim
+        );
+        resolved as usize
+    }).collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+/// Sums `arr` over the (normalized) `axes`.
+fn sum_axes(arr: &ArrayD<f32>, axes: &[usize], keepdims: bool) -> TensorData {
+    let backend = backend::current();
+    if axes.len() == arr.ndim() && !keepdims {
+        return TensorData::Scalar(backend.sum_all(arr));
+    }
+
+    let mut reduced = arr.clone();
+    for &ax in axes.iter().rev() {
+        reduced = backend.sum_axis(&reduced, ax);
+        if keepdims {
+            reduced = reduced.insert_axis(Axis(ax));
+        }
+    }
+    TensorData::from_array(reduced)
+}
+
+/// Broadcasts the gradient of a sum over `axes` back to `input_shape`.
+fn expand_grad(grad_output: &TensorData, axes: &[usize], keepdims: bool, input_shape: &[usize]) -> ArrayD<f32> {
+    let mut expanded = grad_output.to_array();
+    if !keepdims {
+        // Ascending order, so each axis lands at its position in the input.
+        for &ax in axes {
+            expanded = expanded.insert_axis(Axis(ax));
+        }
+    }
+    backend::current().broadcast(&expanded, input_shape)
+}
+
 impl Op for Sum {
     fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
-        let x = &inputs[0].borrow().data;
-
-        match x {
-            TensorData::Scalar(_) => x.clone(),
-            TensorData::Tensor(arr) => {
-                let result = if let Some(axes) = &self.axes {
-                    let axes_sorted: Vec<usize> = {
-                        let mut a = axes.clone();
-                        a.sort();
-                        a
-                    };
-
-                    let mut reduced = arr.clone();
-                    for &ax in axes_sorted.iter().rev() {
-                        reduced = reduced.sum_axis(Axis(ax));
-                        if self.keepdims {
-                            reduced = reduced.insert_axis(Axis(ax));
-                        }
-                    }
-                    reduced
-                }
-                else {
-                    let sum = arr.sum();
-                    if self.keepdims {
-                        let shape = vec![1; arr.ndim()];
-                        ArrayD::from_elem(IxDyn(&shape), sum)
-                    } else {
-                        return TensorData::Scalar(sum);
-                    }
-                };
-                TensorData::Tensor(result)
-            }
+        match inputs[0].borrow().value() {
+            x @ TensorData::Scalar(_) => x.clone(),
+            TensorData::Tensor(arr) => sum_axes(arr, &normalize_axes(&self.axes, arr.ndim()), self.keepdims)
         }
     }
 
     fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
-        let input = &output.borrow().parents[0];
-        let input_shape = match &input.borrow().data {
-            TensorData::Tensor(arr) => arr.shape().to_vec(),
-            TensorData::Scalar(_) => vec![]
-        };
-        
-        let grad = match grad_output {
-            TensorData::Scalar(val) => {
-                if input_shape.is_empty() {
-                    TensorData::Scalar(*val)
-                } else {
-                    TensorData::Tensor(ArrayD::from_elem(IxDyn(&input_shape), *val))
-                }
-            },
-
-            TensorData::Tensor(arr) => {
-                if let Some(axes) = &self.axes {
-                    let mut expanded = arr.clone();
-                    if !self.keepdims {
-                        for &ax in axes {
-                            expanded = expanded.insert_axis(Axis(ax));
-                        }
-                    }
-
-                    let broadcasted = expanded.broadcast(IxDyn(&input_shape)).expect("Broadcast failed in backward").to_owned();
-                    TensorData::Tensor(broadcasted)
-                } else {
-                    let val = arr.sum();
-                    TensorData::Tensor(ArrayD::from_elem(IxDyn(&input_shape), val))
-                }
-            }
-        };
+        let input_shape = output.borrow().parents[0].borrow().value().shape();
+        if input_shape.is_empty() {
+            return vec![grad_output.clone()];
+        }
 
-        vec![grad]
+        let axes = normalize_axes(&self.axes, input_shape.len());
+        vec![TensorData::Tensor(expand_grad(grad_output, &axes, self.keepdims, &input_shape))]
     }
 
     fn name(&self) -> &'static str { "Sum" }
@@ -82,109 +76,290 @@ impl Op for Sum {
 
 impl Op for Mean {
     fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
-        let x = &inputs[0].borrow().data;
-
-        match x {
-            TensorData::Scalar(_) => x.clone(),
+        match inputs[0].borrow().value() {
+            x @ TensorData::Scalar(_) => x.clone(),
             TensorData::Tensor(arr) => {
-                let result = if let Some(axes) = &self.axes {
-                    let axes_sorted: Vec<usize> = {
-                        let mut a = axes.clone();
-                        a.sort();
-                        a
-                    };
-
-                    let mut reduced = arr.clone();
-                    for &ax in axes_sorted.iter().rev() {
-                        reduced = reduced.mean_axis(Axis(ax)).expect("Error in forward mean");
-                        if self.keepdims {
-                            reduced = reduced.insert_axis(Axis(ax));
-                        }
-                    }
-                    reduced
-                }
-                else {
-                    let mean = arr.mean().expect("Error in forward mean");
-                    if self.keepdims {
-                        let shape = vec![1; arr.ndim()];
-                        ArrayD::from_elem(IxDyn(&shape), mean)
-                    } else {
-                        return TensorData::Scalar(mean);
-                    }
-                };
-                TensorData::Tensor(result)
+                let axes = normalize_axes(&self.axes, arr.ndim());
+                let count = axes.iter().map(|&ax| arr.shape()[ax]).product::<usize>() as f32;
+                sum_axes(arr, &axes, self.keepdims).map(|v| v / count)
             }
         }
     }
 
     fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
-        let input = &output.borrow().parents[0];
-        let input_shape = match &input.borrow().data {
-            TensorData::Tensor(arr) => arr.shape().to_vec(),
-            TensorData::Scalar(_) => vec![]
-        };
+        let input_shape = output.borrow().parents[0].borrow().value().shape();
+        if input_shape.is_empty() {
+            return vec![grad_output.clone()];
+        }
+
+        let axes = normalize_axes(&self.axes, input_shape.len());
+        let count = axes.iter().map(|&ax| input_shape[ax]).product::<usize>() as f32;
+        let grad = expand_grad(grad_output, &axes, self.keepdims, &input_shape);
+        vec![TensorData::Tensor(backend::current().map(&grad, &|v| v / count))]
+    }
+
+    fn name(&self) -> &'static str { "Mean" }
+}
 
-        let total_count = if let Some(axes) = &self.axes {
-            axes.iter().map(|&ax| input_shape[ax]).product::<usize>() as f32
+/// Views a tensor as `outer` contiguous lanes of `inner` elements, one lane per
+/// output element, by moving the reduced axes to the end.
+pub(crate) struct ReduceLayout {
+    order: Vec<usize>,
+    permuted_shape: Vec<usize>,
+    pub out_shape: Vec<usize>,
+    pub inner: usize
+}
+
+impl ReduceLayout {
+    pub fn new(shape: &[usize], axes: &Option<Vec<isize>>, keepdims: bool) -> ReduceLayout {
+        let reduced = normalize_axes(axes, shape.len());
+        let kept: Vec<usize> = (0..shape.len()).filter(|ax| !reduced.contains(ax)).collect();
+        let order: Vec<usize> = kept.iter().chain(&reduced).copied().collect();
+        let out_shape = if keepdims {
+            shape.iter().enumerate().map(|(ax, &d)| if reduced.contains(&ax) { 1 } else { d }).collect()
         } else {
-            input_shape.iter().product::<usize>() as f32
+            kept.iter().map(|&ax| shape[ax]).collect()
         };
-        
-        let grad = match grad_output {
-            TensorData::Scalar(val) => {
-                if input_shape.is_empty() {
-                    TensorData::Scalar(*val / total_count)
-                } else {
-                    let scale = val / total_count;
-                    TensorData::Tensor(ArrayD::from_elem(IxDyn(&input_shape), scale))
+
+        ReduceLayout {
+            permuted_shape: order.iter().map(|&ax| shape[ax]).collect(),
+            inner: reduced.iter().map(|&ax| shape[ax]).product(),
+            order,
+            out_shape
+        }
+    }
+
+    pub fn lanes(&self, arr: &ArrayD<f32>) -> Vec<f32> {
+        permute_array(arr, &self.order).into_iter().collect()
+    }
+
+    /// Inverse of `lanes`: puts lane-ordered values back in the input layout.
+    pub fn restore(&self, data: Vec<f32>) -> ArrayD<f32> {
+        let mut inverse = vec![0; self.order.len()];
+        for (i, &ax) in self.order.iter().enumerate() {
+            inverse[ax] = i;
+        }
+        let permuted = ArrayD::from_shape_vec(IxDyn(&self.permuted_shape), data).unwrap();
+        permute_array(&permuted, &inverse)
+    }
+}
+
+/// Larger of `a` and `b`, or NaN if either is NaN (unlike `f32::max`).
+pub(crate) fn nan_max(a: f32, b: f32) -> f32 {
+    if a.is_nan() || a > b { a } else { b }
+}
+
+/// Smaller of `a` and `b`, or NaN if either is NaN (unlike `f32::min`).
+pub(crate) fn nan_min(a: f32, b: f32) -> f32 {
+    if a.is_nan() || a < b { a } else { b }
+}
+
+/// `grad` split evenly between the positions of `lane` equal to `target`.
+fn split_among_ties(lane: &[f32], target: f32, grad: f32) -> Vec<f32> {
+    let ties = lane.iter().filter(|&&v| v == target).count() as f32;
+    lane.iter().map(|&v| if v == target { grad / ties } else { 0.0 }).collect()
+}
+
+impl ReduceKind {
+    fn reduce(&self, lane: &[f32]) -> f32 {
+        let n = lane.len() as f32;
+        match *self {
+            ReduceKind::Max => lane.iter().copied().fold(f32::NEG_INFINITY, nan_max),
+            ReduceKind::Min => lane.iter().copied().fold(f32::INFINITY, nan_min),
+            ReduceKind::Prod => lane.iter().product(),
+            ReduceKind::Var { unbiased } => {
+                let mean = lane.iter().sum::<f32>() / n;
+                lane.iter().map(|&x| (x - mean) * (x - mean)).sum::<f32>() / (n - unbiased as u8 as f32)
+            },
+            ReduceKind::Std { unbiased } => ReduceKind::Var { unbiased }.reduce(lane).sqrt(),
+            ReduceKind::LogSumExp => {
+                let max = ReduceKind::Max.reduce(lane);
+                if max.is_infinite() {
+                    return max;
                 }
+                max + lane.iter().map(|&x| (x - max).exp()).sum::<f32>().ln()
             },
+            ReduceKind::Norm { p } if p == f32::INFINITY => lane.iter().fold(0.0, |m, x| nan_max(m, x.abs())),
+            ReduceKind::Norm { p } => lane.iter().map(|x| x.abs().powf(p)).sum::<f32>().powf(1.0 / p)
+        }
+    }
 
-            TensorData::Tensor(arr) => {
-                if let Some(axes) = &self.axes {
-                    let mut expanded = arr.clone();
-                    if !self.keepdims {
-                        for &ax in axes {
-                            expanded = expanded.insert_axis(Axis(ax));
-                        }
-                    }
-
-                    let broadcasted = expanded
-                        .broadcast(IxDyn(&input_shape))
-                        .expect("Broadcast failed in backward")
-                        .to_owned()
-                        / total_count;
-                    TensorData::Tensor(broadcasted)
-                } else {
-                    let val = arr.mean().unwrap();
-                    TensorData::Tensor(ArrayD::from_elem(IxDyn(&input_shape), val))
+    /// Gradient of one lane given its reduced value `out` and upstream gradient `g`.
+    fn backward(&self, lane: &[f32], out: f32, g: f32) -> Vec<f32> {
+        let n = lane.len() as f32;
+        match *self {
+            ReduceKind::Max | ReduceKind::Min => split_among_ties(lane, out, g),
+            ReduceKind::Prod => {
+                // Product of the other elements, without dividing by zero.
+                let zeros = lane.iter().filter(|&&x| x == 0.0).count();
+                match zeros {
+                    0 => lane.iter().map(|&x| g * out / x).collect(),
+                    1 => {
+                        let others: f32 = lane.iter().filter(|&&x| x != 0.0).product();
+                        lane.iter().map(|&x| if x == 0.0 { g * others } else { 0.0 }).collect()
+                    },
+                    _ => vec![0.0; lane.len()]
+                }
+            },
+            ReduceKind::Var { unbiased } => {
+                let mean = lane.iter().sum::<f32>() / n;
+                let denom = n - unbiased as u8 as f32;
+                lane.iter().map(|&x| g * 2.0 * (x - mean) / denom).collect()
+            },
+            ReduceKind::Std { unbiased } => {
+                if out == 0.0 {
+                    return vec![0.0; lane.len()];
+                }
+                ReduceKind::Var { unbiased }.backward(lane, out * out, g / (2.0 * out))
+            },
+            ReduceKind::LogSumExp => {
+                if out == f32::NEG_INFINITY {
+                    return vec![0.0; lane.len()];
                 }
+                lane.iter().map(|&x| g * (x - out).exp()).collect()
+            },
+            ReduceKind::Norm { p } if p == f32::INFINITY => {
+                let abs: Vec<f32> = lane.iter().map(|x| x.abs()).collect();
+                split_among_ties(&abs, out, g).into_iter().zip(lane).map(|(d, x)| d * x.signum()).collect()
+            },
+            ReduceKind::Norm { p } => {
+                if out == 0.0 {
+                    return vec![0.0; lane.len()];
+                }
+                lane.iter().map(|&x| {
+                    if x == 0.0 { 0.0 } else { g * x.signum() * (x.abs() / out).powf(p - 1.0) }
+                }).collect()
             }
+        }
+    }
+}
+
+impl Op for Reduce {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = inputs[0].borrow().value().to_array();
+        let layout = ReduceLayout::new(arr.shape(), &self.axes, self.keepdims);
+        if let ReduceKind::Max | ReduceKind::Min = self.kind {
+            assert!(layout.inner > 0, "Cannot take the {:?} of an empty tensor", self.kind);
+        }
+
+        let lanes = layout.lanes(&arr);
+        let out = if layout.inner == 0 {
+            vec![self.kind.reduce(&[]); layout.out_shape.iter().product()]
+        } else {
+            lanes.chunks(layout.inner).map(|lane| self.kind.reduce(lane)).collect()
         };
+        TensorData::from_array(backend::current().array(&layout.out_shape, out))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let output_borrow = output.borrow();
+        let arr = output_borrow.parents[0].borrow().value().to_array();
+        let layout = ReduceLayout::new(arr.shape(), &self.axes, self.keepdims);
+        if layout.inner == 0 {
+            return vec![TensorData::from_array(backend::current().zeros(arr.shape()))];
+        }
 
-        vec![grad]
+        let out = output_borrow.value().to_array();
+        let grad = grad_output.to_array();
+        let lanes = layout.lanes(&arr);
+        let grads: Vec<f32> = lanes.chunks(layout.inner)
+            .zip(out.iter().zip(grad.iter()))
+            .flat_map(|(lane, (&o, &g))| self.kind.backward(lane, o, g))
+            .collect();
+        vec![TensorData::from_array(layout.restore(grads))]
     }
 
-    fn name(&self) -> &'static str { "Mean" }
+    fn name(&self) -> &'static str { "Reduce" }
 }
 
-fn apply_reduction_op(a: &TensorRef, op: Rc<dyn Op>) -> TensorRef {
-    let data = op.forward(&[a]);
-    let requires_grad = a.borrow().requires_grad;
-    let result = Tensor::new(data, requires_grad);
+impl Op for ArgReduce {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = inputs[0].borrow().value().to_array();
+        let layout = ReduceLayout::new(arr.shape(), &self.axis.map(|ax| vec![ax]), self.keepdims);
+        assert!(layout.inner > 0, "Cannot take the arg{} of an empty tensor", if self.largest { "max" } else { "min" });
 
-    if requires_grad {
-        result.borrow_mut().parents = vec![a.clone()];
-        result.borrow_mut().grad_fn = Some(op);
+        let out = layout.lanes(&arr).chunks(layout.inner).map(|lane| {
+            // The first NaN wins, matching `max` and `min`.
+            let better = |a: f32, b: f32| !b.is_nan() && (a.is_nan() || if self.largest { a > b } else { a < b });
+            (0..lane.len()).reduce(|best, i| if better(lane[i], lane[best]) { i } else { best }).unwrap() as f32
+        }).collect();
+        TensorData::from_array(backend::current().array(&layout.out_shape, out))
     }
 
-    result
+    fn backward(&self, _output: &TensorRef, _grad_output: &TensorData) -> Vec<TensorData> {
+        unreachable!("argmax/argmin outputs are not part of the autograd graph")
+    }
+
+    fn name(&self) -> &'static str { "ArgReduce" }
 }
 
-pub fn sum(a: &TensorRef, axes: Option<Vec<usize>>, keepdim: bool) -> TensorRef {
-    apply_reduction_op(a, Rc::new(Sum {axes: axes, keepdims: keepdim}))
+fn apply_reduction_op(a: &TensorRef, op: Rc<dyn Op>) -> TensorRef {
+    Tensor::from_op(op, &[a])
 }
 
-pub fn mean(a: &TensorRef, axes: Option<Vec<usize>>, keepdim: bool) -> TensorRef {
-    apply_reduction_op(a, Rc::new(Mean {axes: axes, keepdims: keepdim}))
-}
\ No newline at end of file
+pub fn sum(a: &TensorRef, axes: Option<Vec<isize>>, keepdim: bool) -> TensorRef {
+    apply_reduction_op(a, Rc::new(Sum {axes, keepdims: keepdim}))
+}
+
+pub fn mean(a: &TensorRef, axes: Option<Vec<isize>>, keepdim: bool) -> TensorRef {
+    apply_reduction_op(a, Rc::new(Mean {axes, keepdims: keepdim}))
+}
+
+fn reduce(a: &TensorRef, kind: ReduceKind, axes: Option<Vec<isize>>, keepdims: bool) -> TensorRef {
+    apply_reduction_op(a, Rc::new(Reduce {kind, axes, keepdims}))
+}
+
+/// Largest element over `axes` (all axes when `None`); tied maxima share the gradient.
+pub fn max(a: &TensorRef, axes: Option<Vec<isize>>, keepdim: bool) -> TensorRef {
+    reduce(a, ReduceKind::Max, axes, keepdim)
+}
+
+/// Smallest element over `axes`; tied minima share the gradient.
+pub fn min(a: &TensorRef, axes: Option<Vec<isize>>, keepdim: bool) -> TensorRef {
+    reduce(a, ReduceKind::Min, axes, keepdim)
+}
+
+/// `(max(a, [axis]), argmax(a, axis))`.
+pub fn max_with_indices(a: &TensorRef, axis: isize, keepdim: bool) -> (TensorRef, TensorRef) {
+    (max(a, Some(vec![axis]), keepdim), argmax(a, Some(axis), keepdim))
+}
+
+/// `(min(a, [axis]), argmin(a, axis))`.
+pub fn min_with_indices(a: &TensorRef, axis: isize, keepdim: bool) -> (TensorRef, TensorRef) {
+    (min(a, Some(vec![axis]), keepdim), argmin(a, Some(axis), keepdim))
+}
+
+/// Position of the first maximum along `axis`, or in the flattened tensor when `None`.
+pub fn argmax(a: &TensorRef, axis: Option<isize>, keepdim: bool) -> TensorRef {
+    Tensor::from_op_no_grad(Rc::new(ArgReduce {largest: true, axis, keepdims: keepdim}), &[a])
+}
+
+/// Position of the first minimum along `axis`, or in the flattened tensor when `None`.
+pub fn argmin(a: &TensorRef, axis: Option<isize>, keepdim: bool) -> TensorRef {
+    Tensor::from_op_no_grad(Rc::new(ArgReduce {largest: false, axis, keepdims: keepdim}), &[a])
+}
+
+pub fn prod(a: &TensorRef, axes: Option<Vec<isize>>, keepdim: bool) -> TensorRef {
+    reduce(a, ReduceKind::Prod, axes, keepdim)
+}
+
+/// Variance over `axes`; `unbiased` applies Bessel's correction (divides by `n - 1`).
+pub fn var(a: &TensorRef, axes: Option<Vec<isize>>, unbiased: bool, keepdim: bool) -> TensorRef {
+    reduce(a, ReduceKind::Var {unbiased}, axes, keepdim)
+}
+
+/// Standard deviation over `axes`; see `var`.
+pub fn std(a: &TensorRef, axes: Option<Vec<isize>>, unbiased: bool, keepdim: bool) -> TensorRef {
+    reduce(a, ReduceKind::Std {unbiased}, axes, keepdim)
+}
+
+/// `log(sum(exp(a)))` over `axes`, shifted by the max so it doesn't overflow.
+pub fn logsumexp(a: &TensorRef, axes: Option<Vec<isize>>, keepdim: bool) -> TensorRef {
+    reduce(a, ReduceKind::LogSumExp, axes, keepdim)
+}
+
+/// The `p`-norm over `axes`; pass `f32::INFINITY` for the max norm.
+pub fn norm(a: &TensorRef, p: f32, axes: Option<Vec<isize>>, keepdim: bool) -> TensorRef {
+    assert!(p > 0.0, "norm expects a positive p, got {}", p);
+    reduce(a, ReduceKind::Norm {p}, axes, keepdim)
+}
@@ -0,0 +1,346 @@
+use crate::backend;
+use crate::tensor::*;
+use crate::ops::op_defs::{Op, Reshape, Flatten, Squeeze, Unsqueeze, Expand, Permute, Transpose, Pad, PadMode};
+use ndarray::{ArrayD, Axis, IxDyn};
+use std::rc::Rc;
+
+fn reshape_array(arr: &ArrayD<f32>, shape: &[usize]) -> ArrayD<f32> {
+    arr.to_shape(IxDyn(shape))
+        .unwrap_or_else(|_| panic!("Cannot reshape tensor of shape {:?} into {:?}", arr.shape(), shape))
+        .to_owned()
+}
+
+/// Resolves a `-1` entry of `spec` so the shape holds exactly `size` elements.
+fn infer_shape(spec: &[isize], size: usize) -> Vec<usize> {
+    let inferred: Vec<usize> = spec.iter().enumerate().filter(|&(_, &d)| d == -1).map(|(i, _)| i).collect();
+    assert!(inferred.len() <= 1, "Only one dimension can be inferred, got shape {:?}", spec);
+    assert!(spec.iter().all(|&d| d >= -1), "Invalid dimension in shape {:?}", spec);
+
+    let known: usize = spec.iter().filter(|&&d| d != -1).map(|&d| d as usize).product();
+    let mut shape: Vec<usize> = spec.iter().map(|&d| d.max(0) as usize).collect();
+    if let Some(&i) = inferred.first() {
+        assert!(known != 0 && size.is_multiple_of(known), "Cannot infer dimension of shape {:?} for {} elements", spec, size);
+        shape[i] = size / known;
+    }
+    assert_eq!(shape.iter().product::<usize>(), size, "Shape {:?} is invalid for {} elements", spec, size);
+    shape
+}
+
+/// Sums `grad` over the axes that were broadcast to turn `shape` into `grad`'s shape.
+pub(crate) fn sum_to_shape(grad: &TensorData, shape: &[usize]) -> TensorData {
+    let arr = match grad {
+        TensorData::Tensor(arr) if arr.shape() != shape => arr,
+        _ => return grad.clone()
+    };
+
+    let backend = backend::current();
+    let mut reduced = arr.clone();
+    while reduced.ndim() > shape.len() {
+        reduced = backend.sum_axis(&reduced, 0);
+    }
+    for (ax, &size) in shape.iter().enumerate() {
+        if size == 1 && reduced.shape()[ax] != 1 {
+            reduced = backend.sum_axis(&reduced, ax).insert_axis(Axis(ax));
+        }
+    }
+    TensorData::from_array(reduced)
+}
+
+fn reshape_grad(output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+    let shape = output.borrow().parents[0].borrow().value().shape();
+    vec![TensorData::from_array(reshape_array(&grad_output.to_array(), &shape))]
+}
+
+impl Op for Reshape {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = inputs[0].borrow().value().to_array();
+        let shape = infer_shape(&self.shape, arr.len());
+        TensorData::from_array(reshape_array(&arr, &shape))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        reshape_grad(output, grad_output)
+    }
+
+    fn name(&self) -> &'static str { "Reshape" }
+}
+
+impl Op for Flatten {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = inputs[0].borrow().value().to_array();
+        if arr.ndim() == 0 {
+            return TensorData::Tensor(reshape_array(&arr, &[1]));
+        }
+        assert!(
+            self.start <= self.end && self.end < arr.ndim(),
+            "Invalid flatten range {}..={} for a {}-d tensor", self.start, self.end, arr.ndim()
+        );
+
+        let dims = arr.shape();
+        let mut shape = dims[..self.start].to_vec();
+        shape.push(dims[self.start..=self.end].iter().product());
+        shape.extend_from_slice(&dims[self.end + 1..]);
+        TensorData::Tensor(reshape_array(&arr, &shape))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        reshape_grad(output, grad_output)
+    }
+
+    fn name(&self) -> &'static str { "Flatten" }
+}
+
+impl Op for Squeeze {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = inputs[0].borrow().value().to_array();
+        assert!(self.axis < arr.ndim(), "Axis {} out of range for a {}-d tensor", self.axis, arr.ndim());
+        assert_eq!(arr.shape()[self.axis], 1, "Cannot squeeze axis {} of size {}", self.axis, arr.shape()[self.axis]);
+
+        TensorData::from_array(arr.remove_axis(Axis(self.axis)))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        reshape_grad(output, grad_output)
+    }
+
+    fn name(&self) -> &'static str { "Squeeze" }
+}
+
+impl Op for Unsqueeze {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = inputs[0].borrow().value().to_array();
+        assert!(self.axis <= arr.ndim(), "Axis {} out of range for a {}-d tensor", self.axis, arr.ndim());
+
+        TensorData::Tensor(arr.insert_axis(Axis(self.axis)))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        reshape_grad(output, grad_output)
+    }
+
+    fn name(&self) -> &'static str { "Unsqueeze" }
+}
+
+impl Op for Expand {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = inputs[0].borrow().value().to_array();
+        assert!(self.shape.iter().all(|&d| d >= -1), "Invalid dimension in expand shape {:?}", self.shape);
+        assert!(
+            self.shape.len() >= arr.ndim(),
+            "Cannot expand a {}-d tensor to {} dimensions", arr.ndim(), self.shape.len()
+        );
+
+        let offset = self.shape.len() - arr.ndim();
+        let shape: Vec<usize> = self.shape.iter().enumerate().map(|(i, &d)| {
+            if d == -1 {
+                assert!(i >= offset, "Cannot infer size of new leading dimension {}", i);
+                arr.shape()[i - offset]
+            } else {
+                d as usize
+            }
+        }).collect();
+
+        let expanded = arr
+            .broadcast(IxDyn(&shape))
+            .unwrap_or_else(|| panic!("Cannot expand tensor of shape {:?} to {:?}", arr.shape(), shape));
+        TensorData::Tensor(backend::current().broadcast(&arr, expanded.shape()))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let shape = output.borrow().parents[0].borrow().value().shape();
+        vec![sum_to_shape(grad_output, &shape)]
+    }
+
+    fn name(&self) -> &'static str { "Expand" }
+}
+
+pub(crate) fn permute_array(arr: &ArrayD<f32>, order: &[usize]) -> ArrayD<f32> {
+    arr.view().permuted_axes(IxDyn(order)).as_standard_layout().into_owned()
+}
+
+impl Op for Permute {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = inputs[0].borrow().value().to_array();
+        let mut sorted = self.order.clone();
+        sorted.sort();
+        assert!(
+            sorted == (0..arr.ndim()).collect::<Vec<_>>(),
+            "{:?} is not a permutation of the axes of a {}-d tensor", self.order, arr.ndim()
+        );
+
+        TensorData::from_array(permute_array(&arr, &self.order))
+    }
+
+    fn backward(&self, _output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let mut inverse = vec![0; self.order.len()];
+        for (i, &ax) in self.order.iter().enumerate() {
+            inverse[ax] = i;
+        }
+        vec![TensorData::from_array(permute_array(&grad_output.to_array(), &inverse))]
+    }
+
+    fn name(&self) -> &'static str { "Permute" }
+}
+
+impl Transpose {
+    fn swap(&self, arr: &ArrayD<f32>) -> ArrayD<f32> {
+        let (a, b) = match self.axes {
+            Some(axes) => axes,
+            None if arr.ndim() < 2 => return arr.clone(),
+            None => (arr.ndim() - 2, arr.ndim() - 1)
+        };
+        assert!(a < arr.ndim() && b < arr.ndim(), "Cannot transpose axes {} and {} of a {}-d tensor", a, b, arr.ndim());
+
+        let mut view = arr.view();
+        view.swap_axes(a, b);
+        view.as_standard_layout().into_owned()
+    }
+}
+
+impl Op for Transpose {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        TensorData::from_array(self.swap(&inputs[0].borrow().value().to_array()))
+    }
+
+    fn backward(&self, _output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        vec![TensorData::from_array(self.swap(&grad_output.to_array()))]
+    }
+
+    fn name(&self) -> &'static str { "Transpose" }
+}
+
+impl Pad {
+    /// For every axis, the input position each output position reads, or
+    /// `None` where a constant is written.
+    fn sources(&self, shape: &[usize]) -> Vec<Vec<Option<usize>>> {
+        assert!(
+            self.pads.len() <= shape.len(),
+            "Cannot pad {} dims of a {}-d tensor", self.pads.len(), shape.len()
+        );
+        let offset = shape.len() - self.pads.len();
+
+        shape.iter().enumerate().map(|(ax, &n)| {
+            let (before, after) = if ax < offset { (0, 0) } else { self.pads[ax - offset] };
+            match self.mode {
+                PadMode::Reflect => assert!(
+                    (before < n && after < n) || before + after == 0,
+                    "Reflect padding ({}, {}) must be smaller than the axis size {}", before, after, n
+                ),
+                PadMode::Replicate => assert!(n > 0 || before + after == 0, "Cannot replicate-pad an empty axis"),
+                PadMode::Circular => assert!(
+                    before <= n && after <= n,
+                    "Circular padding ({}, {}) cannot exceed the axis size {}", before, after, n
+                ),
+                PadMode::Constant(_) => {}
+            }
+
+            let (n, last) = (n as isize, n as isize - 1);
+            (0..n + (before + after) as isize).map(|o| {
+                let i = o - before as isize;
+                let source = match self.mode {
+                    PadMode::Constant(_) => return (0..n).contains(&i).then_some(i as usize),
+                    PadMode::Reflect if i < 0 => -i,
+                    PadMode::Reflect if i > last => 2 * last - i,
+                    PadMode::Replicate => i.clamp(0, last),
+                    PadMode::Circular => i.rem_euclid(n),
+                    PadMode::Reflect => i
+                };
+                Some(source as usize)
+            }).collect()
+        }).collect()
+    }
+}
+
+impl Op for Pad {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let arr = match inputs[0].borrow().value() {
+            TensorData::Tensor(arr) => arr.clone(),
+            TensorData::Scalar(_) => panic!("Cannot pad a scalar")
+        };
+        let fill = match self.mode {
+            PadMode::Constant(value) => value,
+            _ => 0.0
+        };
+
+        let sources = self.sources(arr.shape());
+        let shape: Vec<usize> = sources.iter().map(Vec::len).collect();
+        TensorData::Tensor(ArrayD::from_shape_fn(IxDyn(&shape), |idx| {
+            let src: Option<Vec<usize>> = sources.iter().enumerate().map(|(ax, map)| map[idx[ax]]).collect();
+            src.map_or(fill, |src| arr[IxDyn(&src)])
+        }))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        // Cropping for the interior; padded positions fold back onto their sources.
+        let shape = output.borrow().parents[0].borrow().value().shape();
+        let sources = self.sources(&shape);
+        let mut grad = backend::current().zeros(&shape);
+        for (idx, &g) in grad_output.to_array().indexed_iter() {
+            let src: Option<Vec<usize>> = sources.iter().enumerate().map(|(ax, map)| map[idx[ax]]).collect();
+            if let Some(src) = src {
+                grad[IxDyn(&src)] += g;
+            }
+        }
+        vec![TensorData::Tensor(grad)]
+    }
+
+    fn name(&self) -> &'static str { "Pad" }
+}
+
+fn apply_shape_op(a: &TensorRef, op: Rc<dyn Op>) -> TensorRef {
+    Tensor::from_op(op, &[a])
+}
+
+/// Reshapes `a` (in row-major order); one entry of `shape` may be `-1`.
+pub fn reshape(a: &TensorRef, shape: &[isize]) -> TensorRef {
+    apply_shape_op(a, Rc::new(Reshape {shape: shape.to_vec()}))
+}
+
+/// Same as `reshape`; tensors always own their data, so there is no separate view.
+pub fn view(a: &TensorRef, shape: &[isize]) -> TensorRef {
+    reshape(a, shape)
+}
+
+pub fn flatten(a: &TensorRef, start: usize, end: usize) -> TensorRef {
+    apply_shape_op(a, Rc::new(Flatten {start, end}))
+}
+
+/// Removes `axis`, which must have size 1.
+pub fn squeeze(a: &TensorRef, axis: usize) -> TensorRef {
+    apply_shape_op(a, Rc::new(Squeeze {axis}))
+}
+
+/// Inserts a new axis of size 1 at `axis`.
+pub fn unsqueeze(a: &TensorRef, axis: usize) -> TensorRef {
+    apply_shape_op(a, Rc::new(Unsqueeze {axis}))
+}
+
+/// Broadcasts `a` to `shape`; `-1` keeps the existing size of that axis.
+pub fn expand(a: &TensorRef, shape: &[isize]) -> TensorRef {
+    apply_shape_op(a, Rc::new(Expand {shape: shape.to_vec()}))
+}
+
+pub fn broadcast_to(a: &TensorRef, shape: &[usize]) -> TensorRef {
+    expand(a, &shape.iter().map(|&d| d as isize).collect::<Vec<_>>())
+}
+
+/// Reorders the axes of `a`: output axis `i` is input axis `order[i]`.
+pub fn permute(a: &TensorRef, order: &[usize]) -> TensorRef {
+    apply_shape_op(a, Rc::new(Permute {order: order.to_vec()}))
+}
+
+/// Swaps axes `dim0` and `dim1`.
+pub fn transpose(a: &TensorRef, dim0: usize, dim1: usize) -> TensorRef {
+    apply_shape_op(a, Rc::new(Transpose {axes: Some((dim0, dim1))}))
+}
+
+/// Swaps the last two axes of `a`; 0-d and 1-d tensors are returned unchanged.
+pub fn t(a: &TensorRef) -> TensorRef {
+    apply_shape_op(a, Rc::new(Transpose {axes: None}))
+}
+
+/// Pads the trailing `pads.len()` axes of `a`; `pads[i]` gives the `(before, after)`
+/// amounts for axis `ndim - pads.len() + i`.
+pub fn pad(a: &TensorRef, pads: &[(usize, usize)], mode: PadMode) -> TensorRef {
+    apply_shape_op(a, Rc::new(Pad {pads: pads.to_vec(), mode}))
+}
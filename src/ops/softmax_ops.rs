@@ -0,0 +1,82 @@
+use crate::backend;
+use crate::tensor::*;
+use crate::ops::op_defs::{Op, Softmax, LogSoftmax};
+use crate::ops::reduction_ops::{nan_max, normalize_axes};
+use ndarray::{ArrayD, Axis};
+use std::rc::Rc;
+
+/// `axis` resolved against `x`, so that -1 is the last axis.
+fn resolve_axis(x: &ArrayD<f32>, axis: isize) -> usize {
+    normalize_axes(&Some(vec![axis]), x.ndim())[0]
+}
+
+/// `x - max(x)` along `axis`, so every lane's largest entry is 0; a lane with a NaN becomes all NaN.
+fn shift_by_max(x: &ArrayD<f32>, axis: usize) -> ArrayD<f32> {
+    let backend = backend::current();
+    let max = backend.fold_axis(x, axis, f32::NEG_INFINITY, &nan_max).insert_axis(Axis(axis));
+    backend.zip_map(x, &max, &|a, m| a - m)
+}
+
+/// Sum along `axis`, keeping it as a size-1 axis for broadcasting.
+fn sum_keepdim(x: &ArrayD<f32>, axis: usize) -> ArrayD<f32> {
+    backend::current().sum_axis(x, axis).insert_axis(Axis(axis))
+}
+
+impl Op for Softmax {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let backend = backend::current();
+        let x = inputs[0].borrow().value().to_array();
+        let axis = resolve_axis(&x, self.axis);
+        let exps = backend.map(&shift_by_max(&x, axis), &f32::exp);
+        TensorData::Tensor(backend.zip_map(&exps, &sum_keepdim(&exps, axis), &|e, s| e / s))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        // dL/dx = y * (g - sum(g * y)), using the saved output y.
+        let backend = backend::current();
+        let y = output.borrow().value().to_array();
+        let g = grad_output.to_array();
+        let dot = sum_keepdim(&backend.zip_map(&g, &y, &|a, b| a * b), resolve_axis(&y, self.axis));
+        let centered = backend.zip_map(&g, &dot, &|a, b| a - b);
+        vec![TensorData::Tensor(backend.zip_map(&y, &centered, &|a, b| a * b))]
+    }
+
+    fn name(&self) -> &'static str { "Softmax" }
+}
+
+impl Op for LogSoftmax {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let backend = backend::current();
+        let x = inputs[0].borrow().value().to_array();
+        let axis = resolve_axis(&x, self.axis);
+        let shifted = shift_by_max(&x, axis);
+        let log_sum = backend.map(&sum_keepdim(&backend.map(&shifted, &f32::exp), axis), &f32::ln);
+        TensorData::Tensor(backend.zip_map(&shifted, &log_sum, &|z, l| z - l))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        // dL/dx = g - softmax(x) * sum(g), with softmax(x) = exp(saved output).
+        let backend = backend::current();
+        let out = output.borrow().value().to_array();
+        let g = grad_output.to_array();
+        let total = sum_keepdim(&g, resolve_axis(&out, self.axis));
+        let scaled = backend.zip_map(&out, &total, &|o, t| o.exp() * t);
+        vec![TensorData::Tensor(backend.zip_map(&g, &scaled, &|a, b| a - b))]
+    }
+
+    fn name(&self) -> &'static str { "LogSoftmax" }
+}
+
+fn apply_softmax_op(a: &TensorRef, op: Rc<dyn Op>) -> TensorRef {
+    Tensor::from_op(op, &[a])
+}
+
+/// `exp(a) / sum(exp(a))` along `axis` (negative counts from the end), shifted by the per-lane max so large logits don't overflow.
+pub fn softmax(a: &TensorRef, axis: isize) -> TensorRef {
+    apply_softmax_op(a, Rc::new(Softmax {axis}))
+}
+
+/// `log(softmax(a))` along `axis`, computed as `a - max - log(sum(exp(a - max)))`.
+pub fn log_softmax(a: &TensorRef, axis: isize) -> TensorRef {
+    apply_softmax_op(a, Rc::new(LogSoftmax {axis}))
+}
@@ -0,0 +1,96 @@
+use crate::backend;
+use crate::tensor::*;
+use crate::ops::op_defs::{MultiOp, Sort};
+use crate::ops::reduction_ops::{normalize_axes, ReduceLayout};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+impl Sort {
+    /// Lane layouts of the input and of the outputs, whose sorted axis is cut to `k`.
+    fn layouts(&self, shape: &[usize]) -> (ReduceLayout, ReduceLayout, usize) {
+        let axes = Some(vec![self.axis]);
+        let axis = normalize_axes(&axes, shape.len())[0];
+        let k = self.k.unwrap_or(shape[axis]);
+        assert!(k <= shape[axis], "k = {} is larger than the axis size {}", k, shape[axis]);
+
+        let mut out_shape = shape.to_vec();
+        out_shape[axis] = k;
+        (ReduceLayout::new(shape, &axes, true), ReduceLayout::new(&out_shape, &axes, true), k)
+    }
+}
+
+impl MultiOp for Sort {
+    fn num_outputs(&self) -> usize { 2 }
+
+    fn forward(&self, inputs: &[&TensorRef]) -> Vec<TensorData> {
+        let arr = inputs[0].borrow().value().to_array();
+        assert!(arr.ndim() > 0, "Cannot sort a scalar");
+        let (input, output, k) = self.layouts(arr.shape());
+
+        let mut permutation = Vec::new();
+        let mut values = Vec::new();
+        if input.inner > 0 {
+            for lane in input.lanes(&arr).chunks(input.inner) {
+                let mut order: Vec<usize> = (0..lane.len()).collect();
+                // Stable in both directions: ties keep their original order.
+                if self.descending {
+                    order.sort_by(|&i, &j| lane[j].total_cmp(&lane[i]));
+                } else {
+                    order.sort_by(|&i, &j| lane[i].total_cmp(&lane[j]));
+                }
+                order.truncate(k);
+                values.extend(order.iter().map(|&i| lane[i]));
+                permutation.extend(order);
+            }
+        }
+
+        let indices = permutation.iter().map(|&i| i as f32).collect();
+        *self.permutation.borrow_mut() = permutation;
+        vec![
+            TensorData::Tensor(output.restore(values)),
+            TensorData::Tensor(output.restore(indices))
+        ]
+    }
+
+    fn backward(&self, inputs: &[TensorRef], grad_outputs: &[Option<TensorData>]) -> Vec<TensorData> {
+        let shape = inputs[0].borrow().value().shape();
+        let Some(grad) = &grad_outputs[0] else {
+            return vec![TensorData::Tensor(backend::current().zeros(&shape))];
+        };
+        let (input, output, k) = self.layouts(&shape);
+
+        // Scatter each lane's gradient back through the saved permutation.
+        let mut grad_x = vec![0.0; shape.iter().product()];
+        let permutation = self.permutation.borrow();
+        for (j, g) in output.lanes(&grad.to_array()).into_iter().enumerate() {
+            let lane = j / k.max(1);
+            grad_x[lane * input.inner + permutation[j]] += g;
+        }
+        vec![TensorData::Tensor(input.restore(grad_x))]
+    }
+
+    fn name(&self) -> &'static str { "Sort" }
+}
+
+fn apply_sort_op(a: &TensorRef, axis: isize, descending: bool, k: Option<usize>) -> (TensorRef, TensorRef) {
+    let op = Sort {axis, descending, k, permutation: RefCell::new(Vec::new())};
+    let mut outputs = Tensor::from_multi_op(Rc::new(op), &[a]);
+    let indices = outputs.pop().unwrap();
+    (outputs.pop().unwrap(), indices)
+}
+
+/// Sorts `a` along `axis` (stably), returning the sorted values and their
+/// original positions along that axis.
+pub fn sort(a: &TensorRef, axis: isize, descending: bool) -> (TensorRef, TensorRef) {
+    apply_sort_op(a, axis, descending, None)
+}
+
+/// The positions that would sort `a` along `axis`.
+pub fn argsort(a: &TensorRef, axis: isize, descending: bool) -> TensorRef {
+    sort(a, axis, descending).1
+}
+
+/// The `k` largest entries along `axis` in descending order, with their positions.
+pub fn topk(a: &TensorRef, k: usize, axis: isize) -> (TensorRef, TensorRef) {
+    apply_sort_op(a, axis, true, Some(k))
+}
@@ -1,10 +1,13 @@
 use crate::tensor::*;
-use crate::ops::op_defs::{Op, Neg, Abs, ReLU};
+use crate::ops::op_defs::{
+    Op, Neg, Abs, ReLU, Exp, Log, Log1p, Sqrt, Rsqrt, Sin, Cos, Tan, Tanh, Sigmoid, Reciprocal, Square, PowScalar,
+    LeakyReLU, ELU, SELU, GELU, SiLU, Mish, Softplus, Hardtanh, Hardswish
+};
 use std::rc::Rc;
 
 impl Op for Neg {
     fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
-        -&inputs[0].borrow().data
+        -inputs[0].borrow().value()
     }
 
     fn backward(&self, _output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
@@ -14,58 +17,191 @@ impl Op for Neg {
     fn name(&self) -> &'static str { "Neg" }
 }
 
-impl Op for Abs {
-    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
-        let x = &inputs[0].borrow().data;
+/// Multiplies `grad_output` by the local derivative `f(x, y)`, where `x` is
+/// the op's input and `y` its output.
+fn chain(output: &TensorRef, grad_output: &TensorData, f: impl Fn(f32, f32) -> f32) -> Vec<TensorData> {
+    let output_borrow = output.borrow();
+    let derivative = output_borrow.parents[0].borrow().value().zip_with(output_borrow.value(), f);
+    vec![grad_output * &derivative]
+}
+
+macro_rules! impl_elementwise {
+    ($op:ident, $forward:expr, $derivative:expr) => {
+        impl Op for $op {
+            fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+                inputs[0].borrow().value().map($forward)
+            }
 
-        match x {
-            TensorData::Scalar(x) => TensorData::Scalar((*x).abs()),
-            TensorData::Tensor(arr) => TensorData::Tensor(arr.map(|x| (*x).abs()))
+            fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+                chain(output, grad_output, $derivative)
+            }
+
+            fn name(&self) -> &'static str { stringify!($op) }
+
+            fn elementwise(&self) -> Option<fn(f32) -> f32> { Some($forward) }
         }
+    };
+}
+
+fn stable_sigmoid(x: f32) -> f32 {
+    if x >= 0.0 {
+        1.0 / (1.0 + (-x).exp())
+    } else {
+        let e = x.exp();
+        e / (1.0 + e)
     }
+}
 
-    fn backward(&self, _output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
-        match grad_output {
-            TensorData::Scalar(x) => vec![TensorData::Scalar((*x) * (*x).signum())],
-            TensorData::Tensor(arr) => vec![TensorData::Tensor(arr.map(|x| (*x) * (*x).signum()))]
-        }
+// Subgradient 0 at x = 0 for both.
+impl_elementwise!(Abs, f32::abs, |x, _| if x == 0.0 { 0.0 } else { x.signum() });
+impl_elementwise!(ReLU, |x| x.max(0.0), |x, _| (x > 0.0) as u8 as f32);
+
+impl_elementwise!(Exp, f32::exp, |_, y| y);
+impl_elementwise!(Log, f32::ln, |x, _| 1.0 / x);
+impl_elementwise!(Log1p, f32::ln_1p, |x, _| 1.0 / (1.0 + x));
+impl_elementwise!(Sqrt, f32::sqrt, |_, y| 0.5 / y);
+impl_elementwise!(Rsqrt, |x| 1.0 / x.sqrt(), |_, y| -0.5 * y * y * y);
+impl_elementwise!(Sin, f32::sin, |x, _| x.cos());
+impl_elementwise!(Cos, f32::cos, |x, _| -x.sin());
+impl_elementwise!(Tan, f32::tan, |_, y| 1.0 + y * y);
+impl_elementwise!(Tanh, f32::tanh, |_, y| 1.0 - y * y);
+impl_elementwise!(Sigmoid, stable_sigmoid, |_, y| y * (1.0 - y));
+impl_elementwise!(Reciprocal, f32::recip, |_, y| -y * y);
+impl_elementwise!(Square, |x| x * x, |x, _| 2.0 * x);
+
+/// `log(1 + exp(x))` without overflow for large `x`.
+fn stable_softplus(x: f32) -> f32 {
+    x.max(0.0) + (-x.abs()).exp().ln_1p()
+}
+
+/// Error function (Abramowitz & Stegun 7.1.26, absolute error below 1.5e-7).
+fn erf(x: f32) -> f32 {
+    let x = x as f64;
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    ((1.0 - poly * (-x * x).exp()) * x.signum()) as f32
+}
+
+const SELU_ALPHA: f32 = 1.673_263_2;
+const SELU_SCALE: f32 = 1.050_701;
+const SQRT_2_OVER_PI: f32 = 0.797_884_6;
+const GELU_COEFF: f32 = 0.044715;
+
+impl_elementwise!(
+    SELU,
+    |x| SELU_SCALE * if x > 0.0 { x } else { SELU_ALPHA * x.exp_m1() },
+    |x, y| if x > 0.0 { SELU_SCALE } else { y + SELU_SCALE * SELU_ALPHA }
+);
+impl_elementwise!(SiLU, |x| x * stable_sigmoid(x), |x, _| {
+    let s = stable_sigmoid(x);
+    s * (1.0 + x * (1.0 - s))
+});
+impl_elementwise!(Mish, |x| x * stable_softplus(x).tanh(), |x, _| {
+    let t = stable_softplus(x).tanh();
+    t + x * stable_sigmoid(x) * (1.0 - t * t)
+});
+impl_elementwise!(
+    Hardswish,
+    |x| x * (x + 3.0).clamp(0.0, 6.0) / 6.0,
+    |x, _| if x < -3.0 { 0.0 } else if x > 3.0 { 1.0 } else { (2.0 * x + 3.0) / 6.0 }
+);
+
+impl Op for LeakyReLU {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        inputs[0].borrow().value().map(|x| if x > 0.0 { x } else { self.slope * x })
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        chain(output, grad_output, |x, _| if x > 0.0 { 1.0 } else { self.slope })
     }
 
-    fn name(&self) -> &'static str { "Abs" }
+    fn name(&self) -> &'static str { "LeakyReLU" }
 }
 
-impl Op for ReLU {
+impl Op for ELU {
     fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
-        let x = &inputs[0].borrow().data;
+        inputs[0].borrow().value().map(|x| if x > 0.0 { x } else { self.alpha * x.exp_m1() })
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        // For x <= 0, d/dx alpha * (e^x - 1) = y + alpha.
+        chain(output, grad_output, |x, y| if x > 0.0 { 1.0 } else { y + self.alpha })
+    }
 
-        match x {
-            TensorData::Scalar(x) => TensorData::Scalar((*x).max(0.0f32)),
-            TensorData::Tensor(arr) => TensorData::Tensor(arr.map(|x| (*x).max(0.0f32)))
+    fn name(&self) -> &'static str { "ELU" }
+}
+
+impl Op for GELU {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        if self.approximate {
+            inputs[0].borrow().value().map(|x| 0.5 * x * (1.0 + (SQRT_2_OVER_PI * (x + GELU_COEFF * x * x * x)).tanh()))
+        } else {
+            inputs[0].borrow().value().map(|x| 0.5 * x * (1.0 + erf(x * std::f32::consts::FRAC_1_SQRT_2)))
         }
     }
 
-    fn backward(&self, _output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
-        match grad_output {
-            TensorData::Scalar(x) => vec![TensorData::Scalar((*x) * ((*x) > 0.0f32) as u8 as f32)],
-            TensorData::Tensor(arr) => vec![TensorData::Tensor(arr.map(|x| (*x) * ((*x) > 0.0f32) as u8 as f32))]
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        if self.approximate {
+            chain(output, grad_output, |x, _| {
+                let t = (SQRT_2_OVER_PI * (x + GELU_COEFF * x * x * x)).tanh();
+                0.5 * (1.0 + t) + 0.5 * x * (1.0 - t * t) * SQRT_2_OVER_PI * (1.0 + 3.0 * GELU_COEFF * x * x)
+            })
+        } else {
+            // Phi(x) + x * phi(x)
+            chain(output, grad_output, |x, _| {
+                let cdf = 0.5 * (1.0 + erf(x * std::f32::consts::FRAC_1_SQRT_2));
+                let pdf = (-0.5 * x * x).exp() * SQRT_2_OVER_PI * 0.5;
+                cdf + x * pdf
+            })
         }
     }
 
-    fn name(&self) -> &'static str { "ReLU" }
+    fn name(&self) -> &'static str { "GELU" }
 }
 
+impl Op for Softplus {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let Softplus { beta, threshold } = *self;
+        inputs[0].borrow().value().map(|x| if beta * x > threshold { x } else { stable_softplus(beta * x) / beta })
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let Softplus { beta, threshold } = *self;
+        chain(output, grad_output, |x, _| if beta * x > threshold { 1.0 } else { stable_sigmoid(beta * x) })
+    }
 
-fn apply_unary_op(a: &TensorRef, op: Rc<dyn Op>) -> TensorRef {
-    let data = op.forward(&[a]);
-    let requires_grad = a.borrow().requires_grad;
-    let result = Tensor::new(data, requires_grad);
+    fn name(&self) -> &'static str { "Softplus" }
+}
+
+impl Op for Hardtanh {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        assert!(self.min <= self.max, "hardtanh expects min <= max, got {} > {}", self.min, self.max);
+        inputs[0].borrow().value().map(|x| x.clamp(self.min, self.max))
+    }
+
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        chain(output, grad_output, |x, _| (x > self.min && x < self.max) as u8 as f32)
+    }
+
+    fn name(&self) -> &'static str { "Hardtanh" }
+}
+
+impl Op for PowScalar {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        inputs[0].borrow().value().map(|x| x.powf(self.exponent))
+    }
 
-    if requires_grad {
-        result.borrow_mut().parents = vec![a.clone()];
-        result.borrow_mut().grad_fn = Some(op);
+    fn backward(&self, output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        let p = self.exponent;
+        // Avoid 0 * inf at x = 0 when the exponent is 0.
+        chain(output, grad_output, |x, _| if p == 0.0 { 0.0 } else { p * x.powf(p - 1.0) })
     }
 
-    result
+    fn name(&self) -> &'static str { "PowScalar" }
+}
+
+fn apply_unary_op(a: &TensorRef, op: Rc<dyn Op>) -> TensorRef {
+    Tensor::from_op(op, &[a])
 }
 
 pub fn neg(a: &TensorRef) -> TensorRef {
@@ -78,4 +214,118 @@ pub fn abs(a: &TensorRef) -> TensorRef {
 
 pub fn relu(a: &TensorRef) -> TensorRef {
     apply_unary_op(a, Rc::new(ReLU))
-}
\ No newline at end of file
+}
+
+pub fn exp(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Exp))
+}
+
+/// Natural logarithm.
+pub fn log(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Log))
+}
+
+/// `log(1 + a)`, accurate for small `a`.
+pub fn log1p(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Log1p))
+}
+
+pub fn sqrt(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Sqrt))
+}
+
+/// `1 / sqrt(a)`.
+pub fn rsqrt(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Rsqrt))
+}
+
+/// `a ^ exponent`; see `pow` for a tensor exponent.
+pub fn pow_scalar(a: &TensorRef, exponent: f32) -> TensorRef {
+    apply_unary_op(a, Rc::new(PowScalar {exponent}))
+}
+
+pub fn sin(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Sin))
+}
+
+pub fn cos(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Cos))
+}
+
+pub fn tan(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Tan))
+}
+
+pub fn tanh(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Tanh))
+}
+
+/// `1 / (1 + exp(-a))`, computed without overflow for large `|a|`.
+pub fn sigmoid(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Sigmoid))
+}
+
+/// `1 / a`.
+pub fn reciprocal(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Reciprocal))
+}
+
+pub fn square(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Square))
+}
+
+/// `a` where positive, `slope * a` elsewhere.
+pub fn leaky_relu(a: &TensorRef, slope: f32) -> TensorRef {
+    apply_unary_op(a, Rc::new(LeakyReLU {slope}))
+}
+
+/// `a` where positive, `alpha * (exp(a) - 1)` elsewhere.
+pub fn elu(a: &TensorRef, alpha: f32) -> TensorRef {
+    apply_unary_op(a, Rc::new(ELU {alpha}))
+}
+
+/// Scaled ELU with the self-normalizing constants.
+pub fn selu(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(SELU))
+}
+
+/// `a * Phi(a)`; `approximate` selects the tanh approximation.
+pub fn gelu(a: &TensorRef, approximate: bool) -> TensorRef {
+    apply_unary_op(a, Rc::new(GELU {approximate}))
+}
+
+/// `a * sigmoid(a)`.
+pub fn silu(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(SiLU))
+}
+
+/// Same as `silu`.
+pub fn swish(a: &TensorRef) -> TensorRef {
+    silu(a)
+}
+
+/// `a * tanh(softplus(a))`.
+pub fn mish(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Mish))
+}
+
+/// `log(1 + exp(beta * a)) / beta`, reverting to `a` once `beta * a > threshold`.
+pub fn softplus(a: &TensorRef, beta: f32, threshold: f32) -> TensorRef {
+    assert!(beta > 0.0, "softplus expects a positive beta, got {}", beta);
+    apply_unary_op(a, Rc::new(Softplus {beta, threshold}))
+}
+
+/// Clamps `a` to `[min, max]`.
+pub fn hardtanh(a: &TensorRef, min: f32, max: f32) -> TensorRef {
+    apply_unary_op(a, Rc::new(Hardtanh {min, max}))
+}
+
+/// `a * relu6(a + 3) / 6`.
+pub fn hardswish(a: &TensorRef) -> TensorRef {
+    apply_unary_op(a, Rc::new(Hardswish))
+}
+
+/// Clamps `a` to `[0, 6]`.
+pub fn relu6(a: &TensorRef) -> TensorRef {
+    hardtanh(a, 0.0, 6.0)
+}
@@ -0,0 +1,161 @@
+use crate::tensor::*;
+use std::cell::RefCell;
+
+const DEFAULT_SEED: u64 = 0x5EED;
+
+/// Seedable pseudo-random generator (xoshiro256**, seeded through SplitMix64).
+///
+/// The same seed always yields the same sequence, independent of platform or
+/// of any external crate, so initializations and shuffles are reproducible.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    state: [u64; 4]
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        let mut sm = seed;
+        let mut next = || {
+            sm = sm.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = sm;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^ (z >> 31)
+        };
+        Generator { state: [next(), next(), next(), next()] }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+
+        result
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 * (1.0 / (1u64 << 24) as f32)
+    }
+
+    /// Uniform integer in `[0, n)`, without modulo bias.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "Cannot sample from an empty range");
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    fn normals(&mut self, count: usize) -> Vec<f32> {
+        // Box-Muller, using both values of each pair.
+        let mut values = Vec::with_capacity(count + 1);
+        while values.len() < count {
+            let u1 = 1.0 - self.next_f32() as f64;
+            let u2 = self.next_f32() as f64;
+            let r = (-2.0 * u1.ln()).sqrt();
+            let theta = 2.0 * std::f64::consts::PI * u2;
+            values.push((r * theta.cos()) as f32);
+            values.push((r * theta.sin()) as f32);
+        }
+        values.truncate(count);
+        values
+    }
+
+    /// Uniform samples in `[0, 1)`.
+    pub fn rand(&mut self, shape: &[usize], requires_grad: bool) -> TensorRef {
+        let data = (0..shape.iter().product()).map(|_| self.next_f32()).collect();
+        Tensor::from_vec(data, shape, requires_grad)
+    }
+
+    /// Standard normal samples.
+    pub fn randn(&mut self, shape: &[usize], requires_grad: bool) -> TensorRef {
+        let data = self.normals(shape.iter().product());
+        Tensor::from_vec(data, shape, requires_grad)
+    }
+
+    /// Integers uniformly drawn from `[low, high)`.
+    pub fn randint(&mut self, low: i64, high: i64, shape: &[usize]) -> TensorRef {
+        assert!(low < high, "randint expects low < high, got [{}, {})", low, high);
+        // Neither the span nor `low + offset` fits in i64 for ranges wider than i64::MAX.
+        let span = high.abs_diff(low);
+        let data = (0..shape.iter().product())
+            .map(|_| low.checked_add_unsigned(self.below(span)).expect("offset stays below high") as f32)
+            .collect();
+        Tensor::from_vec(data, shape, false)
+    }
+
+    /// 0/1 samples, each 1 with the probability given by the matching element of `probs`.
+    pub fn bernoulli(&mut self, probs: &TensorRef) -> TensorRef {
+        let mut sample = |p: f32| {
+            assert!((0.0..=1.0).contains(&p), "bernoulli probabilities must be in [0, 1], got {}", p);
+            (self.next_f32() < p) as u8 as f32
+        };
+
+        match Tensor::data(probs) {
+            TensorData::Scalar(p) => Tensor::new(sample(p), false),
+            TensorData::Tensor(arr) => {
+                let data = arr.iter().map(|&p| sample(p)).collect();
+                Tensor::from_vec(data, arr.shape(), false)
+            }
+        }
+    }
+
+    /// A random permutation of `0..n`.
+    pub fn randperm(&mut self, n: usize) -> TensorRef {
+        let mut perm: Vec<usize> = (0..n).collect();
+        for i in (1..n).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            perm.swap(i, j);
+        }
+        Tensor::from_vec(perm.into_iter().map(|i| i as f32).collect(), &[n], false)
+    }
+}
+
+// Per thread, like the lazy flag and the current backend.
+thread_local! {
+    static GLOBAL: RefCell<Generator> = RefCell::new(Generator::new(DEFAULT_SEED));
+}
+
+/// Reseeds the global generator used by the free functions in this module.
+///
+/// Each thread has its own generator, starting from the same default seed;
+/// this only reseeds the calling thread's. Use a `Generator` directly for a
+/// sequence that does not depend on which thread draws from it.
+pub fn manual_seed(seed: u64) {
+    GLOBAL.with(|g| *g.borrow_mut() = Generator::new(seed));
+}
+
+fn with_global<R>(f: impl FnOnce(&mut Generator) -> R) -> R {
+    GLOBAL.with(|g| f(&mut g.borrow_mut()))
+}
+
+pub fn rand(shape: &[usize], requires_grad: bool) -> TensorRef {
+    with_global(|g| g.rand(shape, requires_grad))
+}
+
+pub fn randn(shape: &[usize], requires_grad: bool) -> TensorRef {
+    with_global(|g| g.randn(shape, requires_grad))
+}
+
+pub fn randint(low: i64, high: i64, shape: &[usize]) -> TensorRef {
+    with_global(|g| g.randint(low, high, shape))
+}
+
+pub fn bernoulli(probs: &TensorRef) -> TensorRef {
+    with_global(|g| g.bernoulli(probs))
+}
+
+pub fn randperm(n: usize) -> TensorRef {
+    with_global(|g| g.randperm(n))
+}
@@ -1,7 +1,9 @@
+use crate::backend;
 use crate::ops::op_defs::*;
-use ndarray::ArrayD;
-use std::rc::Rc;
-use std::cell::RefCell;
+use ndarray::{ArrayD, IxDyn};
+use std::rc::{Rc, Weak};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::ops::{Add as StdAdd, Sub as StdSub, Mul as StdMul, Div as StdDiv, Neg as StdNeg};
 use std::cmp::PartialEq;
@@ -36,14 +38,63 @@ impl From<ArrayD<f32>> for TensorData {
     }
 }
 
+impl TensorData {
+    pub fn shape(&self) -> Vec<usize> {
+        match self {
+            TensorData::Scalar(_) => vec![],
+            TensorData::Tensor(arr) => arr.shape().to_vec()
+        }
+    }
+
+    /// The data as an array; scalars become 0-d arrays.
+    pub fn to_array(&self) -> ArrayD<f32> {
+        match self {
+            TensorData::Scalar(x) => ArrayD::from_elem(IxDyn(&[]), *x),
+            TensorData::Tensor(arr) => arr.clone()
+        }
+    }
+
+    /// Wraps an op result, turning 0-d arrays into scalars.
+    pub fn from_array(arr: ArrayD<f32>) -> TensorData {
+        if arr.ndim() == 0 {
+            TensorData::Scalar(arr.into_iter().next().unwrap())
+        } else {
+            TensorData::Tensor(arr)
+        }
+    }
+
+    /// Applies `f` elementwise using the current backend.
+    pub fn map(&self, f: impl Fn(f32) -> f32) -> TensorData {
+        match self {
+            TensorData::Scalar(x) => TensorData::Scalar(f(*x)),
+            TensorData::Tensor(arr) => TensorData::Tensor(backend::current().map(arr, &f))
+        }
+    }
+
+    /// `map` that overwrites `self` instead of allocating.
+    pub fn map_inplace(&mut self, f: impl Fn(f32) -> f32) {
+        match self {
+            TensorData::Scalar(x) => *x = f(*x),
+            TensorData::Tensor(arr) => backend::current().map_inplace(arr, &f)
+        }
+    }
+
+    /// Applies `f` elementwise to `self` and `other`, broadcasting, using the current backend.
+    pub fn zip_with(&self, other: &TensorData, f: impl Fn(f32, f32) -> f32) -> TensorData {
+        match (self, other) {
+            (TensorData::Scalar(a), TensorData::Scalar(b)) => TensorData::Scalar(f(*a, *b)),
+            (TensorData::Tensor(a), TensorData::Scalar(b)) => TensorData::Tensor(backend::current().map(a, &|x| f(x, *b))),
+            (TensorData::Scalar(a), TensorData::Tensor(b)) => TensorData::Tensor(backend::current().map(b, &|x| f(*a, x))),
+            (TensorData::Tensor(a), TensorData::Tensor(b)) => TensorData::Tensor(backend::current().zip_map(a, b, &f))
+        }
+    }
+}
+
 impl StdNeg for &TensorData {
     type Output = TensorData;
 
     fn neg(self) -> Self::Output {
-        match self {
-            TensorData::Scalar(a) => TensorData::Scalar(-*a),
-            TensorData::Tensor(a) => TensorData::Tensor(-a)
-        }
+        self.map(|x| -x)
     }
 }
 
@@ -61,12 +112,7 @@ impl StdAdd for &TensorData {
     type Output = TensorData;
 
     fn add(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (TensorData::Scalar(a), TensorData::Scalar(b)) => TensorData::Scalar(*a + *b),
-            (TensorData::Tensor(a), TensorData::Scalar(b)) => TensorData::Tensor(a + *b),
-            (TensorData::Scalar(a), TensorData::Tensor(b)) => TensorData::Tensor(*a + b),
-            (TensorData::Tensor(a), TensorData::Tensor(b)) => TensorData::Tensor(a + b)
-        }
+        self.zip_with(rhs, |a, b| a + b)
     }
 }
 
@@ -74,12 +120,7 @@ impl StdSub for &TensorData {
     type Output = TensorData;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (TensorData::Scalar(a), TensorData::Scalar(b)) => TensorData::Scalar(*a - *b),
-            (TensorData::Tensor(a), TensorData::Scalar(b)) => TensorData::Tensor(a - *b),
-            (TensorData::Scalar(a), TensorData::Tensor(b)) => TensorData::Tensor(*a - b),
-            (TensorData::Tensor(a), TensorData::Tensor(b)) => TensorData::Tensor(a - b)
-        }
+        self.zip_with(rhs, |a, b| a - b)
     }
 }
 
@@ -87,112 +128,488 @@ impl StdMul for &TensorData {
     type Output = TensorData;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (TensorData::Scalar(a), TensorData::Scalar(b)) => TensorData::Scalar(*a * *b),
-            (TensorData::Tensor(a), TensorData::Scalar(b)) => TensorData::Tensor(a * *b),
-            (TensorData::Scalar(a), TensorData::Tensor(b)) => TensorData::Tensor(*a * b),
-            (TensorData::Tensor(a), TensorData::Tensor(b)) => TensorData::Tensor(a * b)
-        }
+        self.zip_with(rhs, |a, b| a * b)
     }
 }
 
-
 impl StdDiv for &TensorData {
     type Output = TensorData;
 
     fn div(self, rhs: Self) -> Self::Output {
-        match (self, rhs) {
-            (TensorData::Scalar(a), TensorData::Scalar(b)) => TensorData::Scalar(*a / *b),
-            (TensorData::Tensor(a), TensorData::Scalar(b)) => TensorData::Tensor(a / *b),
-            (TensorData::Scalar(a), TensorData::Tensor(b)) => TensorData::Tensor(*a / b),
-            (TensorData::Tensor(a), TensorData::Tensor(b)) => TensorData::Tensor(a / b)
+        self.zip_with(rhs, |a, b| a / b)
+    }
+}
+
+
+thread_local! {
+    static LAZY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether ops on this thread record unevaluated nodes instead of computing.
+pub fn is_lazy() -> bool {
+    LAZY.with(|l| l.get())
+}
+
+pub fn set_lazy(enabled: bool) {
+    LAZY.with(|l| l.set(enabled));
+}
+
+/// Runs `f` in lazy mode, restoring the previous mode afterwards.
+pub fn lazy<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            set_lazy(self.0);
         }
     }
+
+    let _restore = Restore(LAZY.with(|l| l.replace(true)));
+    f()
+}
+
+/// An op recorded in lazy mode, waiting for `realize()`.
+#[derive(Clone)]
+pub struct LazyNode {
+    pub op: Rc<dyn Op>,
+    pub inputs: Vec<TensorRef>
 }
 
+/// The shared state of the outputs of one `MultiOp` application.
+pub struct MultiNode {
+    pub op: Rc<dyn MultiOp>,
+    pub inputs: Vec<TensorRef>,
+    pub outputs: RefCell<Vec<Weak<RefCell<Tensor>>>>,
+    /// Results computed in lazy mode but not yet moved into their output.
+    pending: RefCell<Vec<Option<TensorData>>>
+}
+
+impl fmt::Debug for MultiNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MultiNode({:?}, {} inputs)", self.op, self.inputs.len())
+    }
+}
+
+/// `grad_fn` of output `index` of a `MultiNode`. The backward engine runs the
+/// node's shared backward once, after the gradients of all outputs are known.
+#[derive(Debug)]
+pub struct OutputSlot {
+    pub node: Rc<MultiNode>,
+    pub index: usize
+}
+
+impl Op for OutputSlot {
+    fn forward(&self, inputs: &[&TensorRef]) -> TensorData {
+        let mut pending = self.node.pending.borrow_mut();
+        if pending.is_empty() {
+            *pending = self.node.op.forward(inputs).into_iter().map(Some).collect();
+        }
+        pending[self.index].take().expect("Multi-output op result already taken")
+    }
+
+    fn backward(&self, _output: &TensorRef, grad_output: &TensorData) -> Vec<TensorData> {
+        // Backward is linear in the output gradients, so this output's share can
+        // be computed on its own; the engine normally batches all outputs instead.
+        let mut grads = vec![None; self.node.op.num_outputs()];
+        grads[self.index] = Some(grad_output.clone());
+        self.node.op.backward(&self.node.inputs, &grads)
+    }
+
+    fn name(&self) -> &'static str { self.node.op.name() }
+
+    fn multi_output(&self) -> Option<(&Rc<MultiNode>, usize)> {
+        Some((&self.node, self.index))
+    }
+}
 
 #[derive(Clone)]
 pub struct Tensor {
-    pub data: TensorData,
+    data: Option<TensorData>,
     pub grad: Option<TensorData>,
     pub requires_grad: bool,
     pub grad_fn: Option<Rc<dyn Op>>,
-    pub parents: Vec<TensorRef>
+    pub parents: Vec<TensorRef>,
+    pub lazy: Option<LazyNode>
 }
 
 impl fmt::Display for Tensor {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, requires_grad: {})", self.data, self.requires_grad)
+        match &self.lazy {
+            Some(node) => write!(f, "(<unrealized {}>, requires_grad: {})", node.op.name(), self.requires_grad),
+            None => write!(f, "({}, requires_grad: {})", self.value(), self.requires_grad)
+        }
+    }
+}
+
+/// Post-order over the graph reachable from `root` through `edges`:
+/// every node appears after all of the nodes it points to.
+fn topo_sort(root: &TensorRef, edges: impl Fn(&Tensor) -> Vec<TensorRef>) -> Vec<TensorRef> {
+    let mut order = vec![];
+    let mut visited = HashSet::new();
+    let mut stack = vec![(root.clone(), false)];
+
+    while let Some((node, expanded)) = stack.pop() {
+        if expanded {
+            order.push(node);
+            continue;
+        }
+        if !visited.insert(Rc::as_ptr(&node)) {
+            continue;
+        }
+
+        let next = edges(&node.borrow());
+        stack.push((node, true));
+        for n in next {
+            if !visited.contains(&Rc::as_ptr(&n)) {
+                stack.push((n, false));
+            }
+        }
     }
+    order
 }
 
 impl Tensor {
     pub fn new<T: Into<TensorData>>(data: T, requires_grad: bool) -> TensorRef {
         Rc::new(RefCell::new(Tensor {
-            data: data.into(),
+            data: Some(data.into()),
             grad: None,
             requires_grad,
             grad_fn: None,
-            parents: vec![]
+            parents: vec![],
+            lazy: None
         }))
     }
 
+    /// Records `op` applied to `inputs`, computing it now or, in lazy mode,
+    /// on the next `realize()`.
+    pub fn from_op(op: Rc<dyn Op>, inputs: &[&TensorRef]) -> TensorRef {
+        let requires_grad = inputs.iter().any(|t| t.borrow().requires_grad);
+        let result = Tensor::compute(op.clone(), inputs, requires_grad);
+
+        if requires_grad {
+            result.borrow_mut().parents = inputs.iter().map(|&t| t.clone()).collect();
+            result.borrow_mut().grad_fn = Some(op);
+        }
+
+        result
+    }
+
+    /// Like `from_op`, but for ops without a gradient (e.g. comparisons): the
+    /// result never requires grad and is not linked into the autograd graph.
+    pub fn from_op_no_grad(op: Rc<dyn Op>, inputs: &[&TensorRef]) -> TensorRef {
+        Tensor::compute(op, inputs, false)
+    }
+
+    fn compute(op: Rc<dyn Op>, inputs: &[&TensorRef], requires_grad: bool) -> TensorRef {
+        if is_lazy() {
+            let result = Tensor::new(0.0, requires_grad);
+            {
+                let mut tensor = result.borrow_mut();
+                tensor.data = None;
+                tensor.lazy = Some(LazyNode {
+                    op,
+                    inputs: inputs.iter().map(|&t| t.clone()).collect()
+                });
+            }
+            result
+        } else {
+            for input in inputs {
+                Tensor::realize(input);
+            }
+            Tensor::new(op.forward(inputs), requires_grad)
+        }
+    }
+
+    /// Records a `MultiOp` applied to `inputs`, returning one tensor per output.
+    pub fn from_multi_op(op: Rc<dyn MultiOp>, inputs: &[&TensorRef]) -> Vec<TensorRef> {
+        let node = Rc::new(MultiNode {
+            op: op.clone(),
+            inputs: inputs.iter().map(|&t| t.clone()).collect(),
+            outputs: RefCell::new(vec![]),
+            pending: RefCell::new(vec![])
+        });
+
+        let outputs: Vec<TensorRef> = (0..op.num_outputs())
+            .map(|index| Tensor::from_op(Rc::new(OutputSlot {node: node.clone(), index}), inputs))
+            .collect();
+        *node.outputs.borrow_mut() = outputs.iter().map(Rc::downgrade).collect();
+
+        outputs
+    }
+
+    pub fn is_realized(self_: &TensorRef) -> bool {
+        self_.borrow().lazy.is_none()
+    }
+
+    /// Computes every unrealized node `self_` depends on, in dependency order.
+    /// Nodes that `self_` does not depend on are left untouched, and an
+    /// intermediate is released as soon as its last consumer has been computed
+    /// (unless it is still referenced elsewhere, e.g. by the autograd graph).
+    /// An elementwise op whose input is such a dying intermediate reuses its
+    /// buffer instead of allocating a new one.
+    pub fn realize(self_: &TensorRef) {
+        if Tensor::is_realized(self_) {
+            return;
+        }
+
+        let schedule = topo_sort(self_, |t| match &t.lazy {
+            Some(node) => node.inputs.iter().filter(|i| !Tensor::is_realized(i)).cloned().collect(),
+            None => vec![]
+        });
+
+        for node in schedule {
+            let Some(LazyNode { op, inputs }) = node.borrow_mut().lazy.take() else { continue };
+            let data = match op.elementwise() {
+                // This node holds the only reference left to its input.
+                Some(f) if inputs.len() == 1 && Rc::strong_count(&inputs[0]) == 1 => {
+                    let mut data = inputs[0].borrow_mut().data.take().expect("Input was realized earlier in the schedule");
+                    data.map_inplace(f);
+                    data
+                }
+                _ => op.forward(&inputs.iter().collect::<Vec<_>>())
+            };
+            node.borrow_mut().data = Some(data);
+        }
+    }
+
+    /// The data of a realized tensor. Panics if it is still pending in lazy
+    /// mode; use `Tensor::data` to realize it first.
+    pub fn value(&self) -> &TensorData {
+        self.data.as_ref().expect("Tensor is not realized yet; call realize() before reading its data")
+    }
+
+    /// The tensor's data, realizing it first if needed.
+    pub fn data(self_: &TensorRef) -> TensorData {
+        Tensor::realize(self_);
+        self_.borrow().value().clone()
+    }
+
     pub fn backward(self_: &TensorRef) {
+        Tensor::realize(self_);
         {
             let mut tensor = self_.borrow_mut();
             if tensor.grad.is_none() {
-                tensor.grad = Some(match &tensor.data {
+                tensor.grad = Some(match tensor.value() {
                     TensorData::Scalar(_) => TensorData::Scalar(1.0),
-                    TensorData::Tensor(x) => TensorData::Tensor(ArrayD::ones(x.raw_dim()))
+                    TensorData::Tensor(x) => TensorData::Tensor(backend::current().ones(x.shape()))
                 });
             }
         }
-        // println!("Starting backward, root grad: {:?}", self_.borrow().grad);
 
-        let mut stack = vec![self_.clone()];
-        while let Some(current) = stack.pop() {
-            let grad = current.borrow().grad.clone().unwrap();
-            // println!("Processing tensor with grad: {}", grad);
-            
-            let (grad_fn, parents) = {
+        // Reverse topological order, so a node's gradient is complete before
+        // it is propagated to its parents.
+        let order = topo_sort(self_, |t| t.parents.clone());
+        let in_graph: HashSet<*const RefCell<Tensor>> = order.iter().map(Rc::as_ptr).collect();
+
+        // A multi-output node runs its backward once its last output in the
+        // graph is reached; by then every output's gradient is complete.
+        let mut remaining: HashMap<*const MultiNode, usize> = HashMap::new();
+        for node in &order {
+            if let Some((multi, _)) = node.borrow().grad_fn.as_ref().and_then(|op| op.multi_output()) {
+                *remaining.entry(Rc::as_ptr(multi)).or_insert(0) += 1;
+            }
+        }
+
+        for current in order.iter().rev() {
+            let (grad, grad_fn, parents) = {
                 let current_ref = current.borrow();
-                (current_ref.grad_fn.clone(), current_ref.parents.clone())
+                (current_ref.grad.clone(), current_ref.grad_fn.clone(), current_ref.parents.clone())
             };
-            // println!("Has grad_fn: {}, num parents: {}", grad_fn.is_some(), parents.len());
-
-            if let Some(op) = grad_fn {
-                // println!("Calling backward on op: {:?}", op);
-                let grads = op.backward(&current, &grad);
-                // println!("Computed gradients: {:?}", grads);
-                
-                for (i, (parent, parent_grad)) in parents.iter().zip(grads).enumerate() {
-                    if parent.borrow().requires_grad {
-                        // println!("Updating parent {}: old_grad={:?}, new_grad={}", 
-                        // i, parent.borrow().grad, parent_grad);
-
-                        let mut p = parent.borrow_mut();
-                        p.grad = Some(match &p.grad {
-                            Some(existing) => existing + &parent_grad,
-                            None => parent_grad,
-                        });
-                        // println!("Parent {} final grad: {:?}", i, p.grad);
-                        drop(p);
-                        stack.push(parent.clone())
+            let Some(op) = grad_fn else { continue };
+
+            let grads = match op.multi_output() {
+                Some((multi, _)) => {
+                    let count = remaining.get_mut(&Rc::as_ptr(multi)).unwrap();
+                    *count -= 1;
+                    if *count > 0 {
+                        continue;
                     }
+
+                    let grad_outputs: Vec<Option<TensorData>> = multi.outputs.borrow().iter()
+                        .map(|output| output.upgrade()
+                            .filter(|t| in_graph.contains(&Rc::as_ptr(t)))
+                            .and_then(|t| t.borrow().grad.clone()))
+                        .collect();
+                    if grad_outputs.iter().all(Option::is_none) {
+                        continue;
+                    }
+                    multi.op.backward(&multi.inputs, &grad_outputs)
+                },
+                None => match grad {
+                    Some(grad) => op.backward(current, &grad),
+                    None => continue
+                }
+            };
+
+            for (parent, parent_grad) in parents.iter().zip(grads) {
+                if parent.borrow().requires_grad {
+                    let mut p = parent.borrow_mut();
+                    p.grad = Some(match &p.grad {
+                        Some(existing) => existing + &parent_grad,
+                        None => parent_grad,
+                    });
                 }
             }
         }
-        // println!("Backward complete");
     }
 }
 
 
 pub trait TensorOps {
     fn backward(&self);
+    fn realize(&self);
+    fn data(&self) -> TensorData;
+    fn t(&self) -> TensorRef;
 }
 
 impl TensorOps for TensorRef {
     fn backward(&self) {
         Tensor::backward(self);
     }
-}
\ No newline at end of file
+
+    fn realize(&self) {
+        Tensor::realize(self);
+    }
+
+    fn data(&self) -> TensorData {
+        Tensor::data(self)
+    }
+
+    fn t(&self) -> TensorRef {
+        crate::ops::t(self)
+    }
+}
+
+/// Nested literal produced by the `tensor!` macro.
+#[derive(Clone, Debug)]
+pub enum Literal {
+    Value(f32),
+    List(Vec<Literal>)
+}
+
+impl Literal {
+    fn flatten_into(&self, depth: usize, shape: &mut Vec<usize>, data: &mut Vec<f32>) {
+        match self {
+            Literal::Value(v) => {
+                assert_eq!(depth, shape.len(), "Ragged tensor literal");
+                data.push(*v);
+            },
+            Literal::List(items) => {
+                if depth == shape.len() {
+                    shape.push(items.len());
+                }
+                assert_eq!(shape[depth], items.len(), "Ragged tensor literal");
+                for item in items {
+                    item.flatten_into(depth + 1, shape, data);
+                }
+            }
+        }
+    }
+}
+
+// Factories
+
+impl Tensor {
+    pub fn full(shape: &[usize], value: f32, requires_grad: bool) -> TensorRef {
+        Tensor::new(backend::current().full(shape, value), requires_grad)
+    }
+
+    pub fn zeros(shape: &[usize], requires_grad: bool) -> TensorRef {
+        Tensor::full(shape, 0.0, requires_grad)
+    }
+
+    pub fn ones(shape: &[usize], requires_grad: bool) -> TensorRef {
+        Tensor::full(shape, 1.0, requires_grad)
+    }
+
+    pub fn zeros_like(other: &TensorRef, requires_grad: bool) -> TensorRef {
+        Tensor::full_like(other, 0.0, requires_grad)
+    }
+
+    pub fn ones_like(other: &TensorRef, requires_grad: bool) -> TensorRef {
+        Tensor::full_like(other, 1.0, requires_grad)
+    }
+
+    pub fn full_like(other: &TensorRef, value: f32, requires_grad: bool) -> TensorRef {
+        match Tensor::data(other) {
+            TensorData::Scalar(_) => Tensor::new(value, requires_grad),
+            TensorData::Tensor(arr) => Tensor::full(arr.shape(), value, requires_grad)
+        }
+    }
+
+    /// Values from `start` up to (excluding) `end` in increments of `step`.
+    pub fn arange(start: f32, end: f32, step: f32, requires_grad: bool) -> TensorRef {
+        assert!(step != 0.0, "arange step must be non-zero");
+        let len = ((end - start) / step).ceil().max(0.0) as usize;
+        let data = (0..len).map(|i| start + i as f32 * step).collect();
+        Tensor::from_vec(data, &[len], requires_grad)
+    }
+
+    /// `steps` evenly spaced values from `start` to `end`, both included.
+    pub fn linspace(start: f32, end: f32, steps: usize, requires_grad: bool) -> TensorRef {
+        let data = match steps {
+            0 => vec![],
+            1 => vec![start],
+            _ => {
+                let step = (end - start) / (steps - 1) as f32;
+                (0..steps).map(|i| start + i as f32 * step).collect()
+            }
+        };
+        Tensor::from_vec(data, &[steps], requires_grad)
+    }
+
+    /// `n x n` identity matrix.
+    pub fn eye(n: usize, requires_grad: bool) -> TensorRef {
+        let mut data = vec![0.0; n * n];
+        for i in 0..n {
+            data[i * n + i] = 1.0;
+        }
+        Tensor::from_vec(data, &[n, n], requires_grad)
+    }
+
+    /// Row-major `data` viewed as `shape`. Panics if the sizes do not match.
+    pub fn from_vec(data: Vec<f32>, shape: &[usize], requires_grad: bool) -> TensorRef {
+        let expected: usize = shape.iter().product();
+        if data.len() != expected {
+            panic!("Cannot build tensor of shape {:?} ({} elements) from {} values", shape, expected, data.len());
+        }
+        Tensor::new(backend::current().array(shape, data), requires_grad)
+    }
+
+    pub fn from_literal(literal: Literal, requires_grad: bool) -> TensorRef {
+        if let Literal::Value(v) = literal {
+            return Tensor::new(v, requires_grad);
+        }
+
+        let (mut shape, mut data) = (vec![], vec![]);
+        literal.flatten_into(0, &mut shape, &mut data);
+        Tensor::from_vec(data, &shape, requires_grad)
+    }
+}
+
+/// Builds a tensor from a (nested) literal:
+/// `tensor!([[1.0, 2.0], [3.0, 4.0]])` or `tensor!([1.0, 2.0], true)` to require gradients.
+#[macro_export]
+macro_rules! tensor {
+    (@lit [$([$($inner:tt)*]),+ $(,)?]) => {
+        $crate::tensor::Literal::List(vec![$($crate::tensor!(@lit [$($inner)*])),+])
+    };
+    (@lit [$($value:expr),* $(,)?]) => {
+        $crate::tensor::Literal::List(vec![$($crate::tensor::Literal::Value($value as f32)),*])
+    };
+    (@lit $value:expr) => {
+        $crate::tensor::Literal::Value($value as f32)
+    };
+    ([$($inner:tt)*], $requires_grad:expr) => {
+        $crate::tensor::Tensor::from_literal($crate::tensor!(@lit [$($inner)*]), $requires_grad)
+    };
+    ([$($inner:tt)*]) => {
+        $crate::tensor!([$($inner)*], false)
+    };
+    ($value:expr, $requires_grad:expr) => {
+        $crate::tensor::Tensor::from_literal($crate::tensor!(@lit $value), $requires_grad)
+    };
+    ($value:expr) => {
+        $crate::tensor!($value, false)
+    };
+}
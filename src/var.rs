@@ -0,0 +1,131 @@
+use crate::ops::{add, sub, mul, div, neg, t};
+use crate::tensor::*;
+use std::fmt;
+use std::ops::{Add as StdAdd, Sub as StdSub, Mul as StdMul, Div as StdDiv, Neg as StdNeg, Deref};
+
+/// Handle around a `TensorRef` supporting arithmetic operators, so
+/// `&a * &x + &b` builds the same graph as `add(&mul(&a, &x), &b)`.
+#[derive(Clone)]
+pub struct Var(pub TensorRef);
+
+impl Var {
+    pub fn new<T: Into<TensorData>>(data: T, requires_grad: bool) -> Var {
+        Var(Tensor::new(data, requires_grad))
+    }
+
+    pub fn tensor(&self) -> &TensorRef {
+        &self.0
+    }
+
+    pub fn data(&self) -> TensorData {
+        Tensor::data(&self.0)
+    }
+
+    pub fn grad(&self) -> Option<TensorData> {
+        self.0.borrow().grad.clone()
+    }
+
+    pub fn backward(&self) {
+        Tensor::backward(&self.0);
+    }
+
+    /// Swaps the last two axes; see `ops::t`.
+    pub fn t(&self) -> Var {
+        Var(t(&self.0))
+    }
+}
+
+impl Deref for Var {
+    type Target = TensorRef;
+
+    fn deref(&self) -> &TensorRef {
+        &self.0
+    }
+}
+
+impl From<TensorRef> for Var {
+    fn from(value: TensorRef) -> Var {
+        Var(value)
+    }
+}
+
+impl From<Var> for TensorRef {
+    fn from(value: Var) -> TensorRef {
+        value.0
+    }
+}
+
+impl fmt::Display for Var {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.borrow())
+    }
+}
+
+fn constant(value: f32) -> TensorRef {
+    Tensor::new(value, false)
+}
+
+macro_rules! impl_operator {
+    ($trait:ident, $method:ident, $op:ident) => {
+        impl $trait<&Var> for &Var {
+            type Output = Var;
+            fn $method(self, rhs: &Var) -> Var { Var($op(&self.0, &rhs.0)) }
+        }
+
+        impl $trait<Var> for &Var {
+            type Output = Var;
+            fn $method(self, rhs: Var) -> Var { Var($op(&self.0, &rhs.0)) }
+        }
+
+        impl $trait<&Var> for Var {
+            type Output = Var;
+            fn $method(self, rhs: &Var) -> Var { Var($op(&self.0, &rhs.0)) }
+        }
+
+        impl $trait<Var> for Var {
+            type Output = Var;
+            fn $method(self, rhs: Var) -> Var { Var($op(&self.0, &rhs.0)) }
+        }
+
+        impl $trait<f32> for &Var {
+            type Output = Var;
+            fn $method(self, rhs: f32) -> Var { Var($op(&self.0, &constant(rhs))) }
+        }
+
+        impl $trait<f32> for Var {
+            type Output = Var;
+            fn $method(self, rhs: f32) -> Var { Var($op(&self.0, &constant(rhs))) }
+        }
+
+        impl $trait<&Var> for f32 {
+            type Output = Var;
+            fn $method(self, rhs: &Var) -> Var { Var($op(&constant(self), &rhs.0)) }
+        }
+
+        impl $trait<Var> for f32 {
+            type Output = Var;
+            fn $method(self, rhs: Var) -> Var { Var($op(&constant(self), &rhs.0)) }
+        }
+    };
+}
+
+impl_operator!(StdAdd, add, add);
+impl_operator!(StdSub, sub, sub);
+impl_operator!(StdMul, mul, mul);
+impl_operator!(StdDiv, div, div);
+
+impl StdNeg for &Var {
+    type Output = Var;
+
+    fn neg(self) -> Var {
+        Var(neg(&self.0))
+    }
+}
+
+impl StdNeg for Var {
+    type Output = Var;
+
+    fn neg(self) -> Var {
+        Var(neg(&self.0))
+    }
+}
@@ -7,7 +7,7 @@ fn test_basic_forward_pass() {
     let y = Tensor::new(3.0, true);
     let result = add(&x, &y);
     
-    assert_eq!(result.borrow().data, 5.0);
+    assert_eq!(Tensor::data(&result), 5.0);
     assert_eq!(result.borrow().requires_grad, true);
 }
 
@@ -20,7 +20,7 @@ fn test_division_gradient() {
     result.backward();
     
     // d/dx(10/x) = -10/x^2 = -10/4 = -2.5
-    assert_eq!(result.borrow().data, 5.0);
+    assert_eq!(Tensor::data(&result), 5.0);
     assert_eq!(x.borrow().grad, Some(-2.5));
 }
 
@@ -39,7 +39,7 @@ fn test_chain_rule() {
     
     result.backward();
     
-    assert_eq!(result.borrow().data, 5.0);
+    assert_eq!(Tensor::data(&result), 5.0);
     assert_eq!(x.borrow().grad, Some(6.0));
 }
 
@@ -56,7 +56,7 @@ fn test_multi_variable_gradients() {
     
     result.backward();
     
-    assert_eq!(result.borrow().data, 8.0); // 2*3 + 2 = 8
+    assert_eq!(Tensor::data(&result), 8.0); // 2*3 + 2 = 8
     assert_eq!(x.borrow().grad, Some(4.0));
     assert_eq!(y.borrow().grad, Some(2.0));
 }
@@ -78,7 +78,7 @@ fn test_complex_expression() {
     
     result.backward();
     
-    assert_eq!(result.borrow().data, 0.75); // (2+1)/(1+3) = 3/4 = 0.75
+    assert_eq!(Tensor::data(&result), 0.75); // (2+1)/(1+3) = 3/4 = 0.75
     assert!((x.borrow().grad.unwrap() - 0.3125).abs() < 1e-6);
 }
 
@@ -92,7 +92,7 @@ fn test_gradient_accumulation() {
     
     result.backward();
     
-    assert_eq!(result.borrow().data, 10.0);
+    assert_eq!(Tensor::data(&result), 10.0);
     assert_eq!(x.borrow().grad, Some(2.0));
 }
 
@@ -105,7 +105,7 @@ fn test_no_grad_propagation() {
     
     result.backward();
     
-    assert_eq!(result.borrow().data, 6.0);
+    assert_eq!(Tensor::data(&result), 6.0);
     assert_eq!(x.borrow().grad, Some(3.0));
     assert_eq!(y.borrow().grad, None);
 }
@@ -126,7 +126,7 @@ fn test_deep_computation_graph() {
     
     result.backward();
     
-    assert_eq!(result.borrow().data, 7.0); // 3*2 + 1 = 7
+    assert_eq!(Tensor::data(&result), 7.0); // 3*2 + 1 = 7
     assert_eq!(x.borrow().grad, Some(3.0));
 }
 
@@ -174,7 +174,7 @@ fn test_floating_point_precision() {
     
     result.backward();
     
-    assert!(approx_eq(result.borrow().data, 0.0, 1e-6));
+    assert!(approx_eq(Tensor::data(&result), 0.0, 1e-6));
     assert_eq!(x.borrow().grad, Some(1.0));
     assert_eq!(y.borrow().grad, Some(1.0));
 }
\ No newline at end of file
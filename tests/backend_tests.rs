@@ -0,0 +1,116 @@
+mod common;
+
+use common::arange;
+use nanograd_rs::backend::{self, Backend, NdarrayBackend, ReferenceBackend};
+use nanograd_rs::tensor::{Tensor, TensorOps};
+use nanograd_rs::ops::{add, mul, sum, mean, matmul, slice, cumsum, cummax, logcumsumexp};
+use ndarray::{Array, Slice};
+use std::rc::Rc;
+
+#[test]
+fn test_reference_matches_ndarray_kernels() {
+    let nd = NdarrayBackend;
+    let reference = ReferenceBackend;
+    let a = arange(&[2, 3, 4]);
+    let b = arange(&[3, 1]);
+
+    assert_eq!(nd.zip_map(&a, &b, &|x, y| x * y + 1.0), reference.zip_map(&a, &b, &|x, y| x * y + 1.0));
+    assert_eq!(nd.map(&a, &|x| x - 2.0), reference.map(&a, &|x| x - 2.0));
+    assert_eq!(nd.broadcast(&b, &[2, 3, 4]), reference.broadcast(&b, &[2, 3, 4]));
+    for axis in 0..3 {
+        assert_eq!(nd.sum_axis(&a, axis), reference.sum_axis(&a, axis));
+        assert_eq!(
+            nd.fold_axis(&a, axis, f32::NEG_INFINITY, &f32::max),
+            reference.fold_axis(&a, axis, f32::NEG_INFINITY, &f32::max)
+        );
+    }
+    assert_eq!(nd.sum_all(&a), reference.sum_all(&a));
+}
+
+#[test]
+fn test_reference_matmul() {
+    let a = arange(&[2, 3]);
+    let b = arange(&[3, 4]);
+    let expected = NdarrayBackend.matmul(&a, &b);
+
+    assert_eq!(ReferenceBackend.matmul(&a, &b), expected);
+    assert_eq!(expected.shape(), &[2, 4]);
+    assert_eq!(expected[[1, 2]], 3.0 * 2.0 + 4.0 * 6.0 + 5.0 * 10.0);
+}
+
+#[test]
+fn test_reference_indexing_scan_and_batched_matmul() {
+    let (nd, reference) = (NdarrayBackend, ReferenceBackend);
+    let a = arange(&[2, 3, 4]);
+
+    for ranges in [
+        vec![Slice::from(1..)],
+        vec![Slice::from(..), Slice::new(-3, Some(-1), 1)],
+        vec![Slice::from(..), Slice::from(..), Slice::new(0, None, -2)]
+    ] {
+        let sliced = nd.slice(&a, &ranges);
+        assert_eq!(reference.slice(&a, &ranges), sliced);
+        let src = sliced.mapv(|v| -v);
+        assert_eq!(reference.slice_scatter(&a, &src, &ranges), nd.slice_scatter(&a, &src, &ranges));
+    }
+
+    for axis in 0..3 {
+        assert_eq!(nd.scan_axis(&a, axis, &|acc, x| acc + x), reference.scan_axis(&a, axis, &|acc, x| acc + x));
+    }
+
+    let b = arange(&[2, 4, 5]);
+    assert_eq!(nd.batched_matmul(&a, &b), reference.batched_matmul(&a, &b));
+}
+
+#[test]
+fn test_ops_on_reference_backend() {
+    let run = || {
+        let x = Tensor::new(arange(&[2, 3, 4]) / 10.0, true);
+        let w = Tensor::new(arange(&[4, 2]) / 10.0, true);
+        let y = matmul(&slice(&x, &[Slice::from(..), Slice::new(0, None, 2)]), &w);
+        let scans = add(&cummax(&x, -1), &logcumsumexp(&x, 0));
+        let loss = add(&sum(&cumsum(&y, 1), None, false), &sum(&scans, None, false));
+        loss.backward();
+
+        let grad_x = x.borrow().grad.clone().unwrap();
+        let grad_w = w.borrow().grad.clone().unwrap();
+        (loss.borrow().value().clone(), grad_x, grad_w)
+    };
+
+    let (loss_nd, gx_nd, gw_nd) = run();
+    let (loss_ref, gx_ref, gw_ref) = backend::with_backend(Rc::new(ReferenceBackend), run);
+
+    assert!(&loss_nd == &loss_ref);
+    assert!(&gx_nd == &gx_ref);
+    assert!(&gw_nd == &gw_ref);
+}
+
+#[test]
+fn test_with_backend_restores_previous() {
+    assert_eq!(backend::current().name(), "ndarray");
+    backend::with_backend(Rc::new(ReferenceBackend), || {
+        assert_eq!(backend::current().name(), "reference");
+    });
+    assert_eq!(backend::current().name(), "ndarray");
+}
+
+#[test]
+fn test_autograd_on_reference_backend() {
+    let run = || {
+        let x = Tensor::new(arange(&[2, 3]), true);
+        let w = Tensor::new(Array::from_vec(vec![1.0, -1.0, 2.0]).into_dyn(), true);
+        let loss = mean(&sum(&mul(&add(&x, &w), &w), Some(vec![1]), false), None, false);
+        loss.backward();
+
+        let grad_x = x.borrow().grad.clone().unwrap();
+        let grad_w = w.borrow().grad.clone().unwrap();
+        (loss.borrow().value().clone(), grad_x, grad_w)
+    };
+
+    let (loss_nd, gx_nd, gw_nd) = run();
+    let (loss_ref, gx_ref, gw_ref) = backend::with_backend(Rc::new(ReferenceBackend), run);
+
+    assert!(&loss_nd == &loss_ref);
+    assert!(&gx_nd == &gx_ref);
+    assert!(&gw_nd == &gw_ref);
+}
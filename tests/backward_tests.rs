@@ -0,0 +1,14 @@
+use nanograd_rs::tensor::{Tensor, TensorOps};
+use nanograd_rs::ops::{add, mul};
+
+#[test]
+fn test_shared_subexpression_gradient() {
+    // y = x * x, z = y + y, dz/dx = 4x
+    let x = Tensor::new(3.0, true);
+    let y = mul(&x, &x);
+    let z = add(&y, &y);
+
+    z.backward();
+
+    assert_eq!(&x.borrow().grad.clone().unwrap(), &12.0.into());
+}
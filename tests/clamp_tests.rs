@@ -0,0 +1,89 @@
+mod common;
+
+use common::{array, grad, gradcheck};
+use nanograd_rs::ops::*;
+use nanograd_rs::tensor::{Tensor, TensorOps};
+use ndarray::array;
+
+#[test]
+fn test_maximum_minimum_values_and_broadcasting() {
+    let a = Tensor::new(array![[1.0, 5.0, 3.0], [-2.0, 0.0, 4.0]].into_dyn(), false);
+    let b = Tensor::new(array![2.0, 2.0, 3.0].into_dyn(), false);
+
+    assert_eq!(array(&maximum(&a, &b)), array![[2.0, 5.0, 3.0], [2.0, 2.0, 4.0]].into_dyn());
+    assert_eq!(array(&minimum(&a, &b)), array![[1.0, 2.0, 3.0], [-2.0, 0.0, 3.0]].into_dyn());
+}
+
+#[test]
+fn test_maximum_minimum_propagate_nan() {
+    let a = Tensor::new(array![f32::NAN, 1.0, 2.0].into_dyn(), false);
+    let b = Tensor::new(array![0.0, f32::NAN, 3.0].into_dyn(), false);
+
+    for out in [maximum(&a, &b), maximum(&b, &a), minimum(&a, &b), minimum(&b, &a)] {
+        let out = array(&out);
+        assert!(out[0].is_nan() && out[1].is_nan());
+    }
+    assert_eq!(array(&maximum(&a, &b))[2], 3.0);
+    assert_eq!(array(&minimum(&a, &b))[2], 2.0);
+}
+
+#[test]
+fn test_maximum_with_zero_matches_relu_gradient() {
+    let values = array![-1.0, 0.0, 2.0].into_dyn();
+    let x = Tensor::new(values.clone(), true);
+    let zero = Tensor::new(0.0, true);
+    sum(&maximum(&x, &zero), None, false).backward();
+
+    let y = Tensor::new(values, true);
+    sum(&relu(&y), None, false).backward();
+
+    assert_eq!(grad(&x), grad(&y));
+    assert_eq!(grad(&x), array![0.0, 0.0, 1.0].into_dyn());
+    // Ties and losses both count towards the constant.
+    assert_eq!(grad(&zero), ndarray::arr0(2.0).into_dyn());
+}
+
+#[test]
+fn test_clamp_scalar_bounds() {
+    let x = Tensor::new(array![-3.0, -1.0, 0.5, 1.0, 4.0].into_dyn(), true);
+
+    assert_eq!(array(&clamp_scalar(&x, Some(-1.0), None)), array![-1.0, -1.0, 0.5, 1.0, 4.0].into_dyn());
+    assert_eq!(array(&clamp_scalar(&x, None, Some(1.0))), array![-3.0, -1.0, 0.5, 1.0, 1.0].into_dyn());
+
+    let clamped = clamp_scalar(&x, Some(-1.0), Some(1.0));
+    assert_eq!(array(&clamped), array(&hardtanh(&x, -1.0, 1.0)));
+    sum(&clamped, None, false).backward();
+    assert_eq!(grad(&x), array![0.0, 0.0, 1.0, 0.0, 0.0].into_dyn());
+}
+
+#[test]
+fn test_clamp_tensor_bounds() {
+    // PPO-style clipping of a ratio to [1 - eps, 1 + eps] per element.
+    let ratio = Tensor::new(array![0.5, 0.95, 1.3].into_dyn(), true);
+    let low = Tensor::new(array![0.8, 0.9, 0.8].into_dyn(), true);
+    let high = Tensor::new(array![1.2, 1.1, 1.2].into_dyn(), true);
+
+    let clipped = clamp(&ratio, Some(&low), Some(&high));
+    assert_eq!(array(&clipped), array![0.8, 0.95, 1.2].into_dyn());
+
+    sum(&clipped, None, false).backward();
+    assert_eq!(grad(&ratio), array![0.0, 1.0, 0.0].into_dyn());
+    assert_eq!(grad(&low), array![1.0, 0.0, 0.0].into_dyn());
+    assert_eq!(grad(&high), array![0.0, 0.0, 1.0].into_dyn());
+}
+
+#[test]
+fn test_minmax_gradcheck() {
+    let a = array![[0.3, -1.2, 2.0], [1.4, 0.1, -0.7]].into_dyn();
+    let b = array![0.5, -1.0, 0.2].into_dyn();
+    gradcheck(|t| maximum(&t[0], &t[1]), &[a.clone(), b.clone()]);
+    gradcheck(|t| minimum(&t[0], &t[1]), &[a.clone(), b.clone()]);
+    gradcheck(|t| clamp(&t[0], Some(&t[1]), None), &[a.clone(), b]);
+    gradcheck(|t| clamp_scalar(&t[0], Some(-0.5), Some(0.5)), &[a]);
+}
+
+#[test]
+#[should_panic(expected = "at least one of min and max")]
+fn test_clamp_requires_a_bound() {
+    clamp_scalar(&Tensor::new(1.0, false), None, None);
+}
@@ -0,0 +1,68 @@
+#![allow(dead_code)]
+
+use nanograd_rs::ops::sum;
+use nanograd_rs::tensor::{Tensor, TensorData, TensorOps, TensorRef};
+use ndarray::{Array, ArrayD, IxDyn};
+
+/// `0, 1, 2, ...` laid out in `shape`.
+pub fn arange(shape: &[usize]) -> ArrayD<f32> {
+    let n = shape.iter().product::<usize>();
+    Array::range(0.0, n as f32, 1.0).into_shape_with_order(IxDyn(shape)).unwrap()
+}
+
+/// Values in `[-1, 1]` stepping by 7 modulo `modulus` (an odd prime), so
+/// neighbours differ and max-like ops have no ties.
+pub fn scrambled(shape: &[usize], modulus: usize) -> ArrayD<f32> {
+    let half = (modulus / 2) as f32;
+    arange(shape).mapv(|v| ((v * 7.0) % modulus as f32 - half) / half)
+}
+
+pub fn array(t: &TensorRef) -> ArrayD<f32> {
+    Tensor::data(t).to_array()
+}
+
+pub fn grad(t: &TensorRef) -> ArrayD<f32> {
+    t.borrow().grad.clone().expect("missing gradient").to_array()
+}
+
+pub fn assert_close(actual: &ArrayD<f32>, expected: &ArrayD<f32>, tol: f32) {
+    assert_eq!(actual.shape(), expected.shape(), "shape mismatch");
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!((a - e).abs() <= tol * (1.0 + e.abs()), "{} != {}\nactual: {}\nexpected: {}", a, e, actual, expected);
+    }
+}
+
+/// Compares the analytic gradients of `sum(f(inputs))` against central differences.
+pub fn gradcheck(f: impl Fn(&[TensorRef]) -> TensorRef, inputs: &[ArrayD<f32>]) {
+    let eps = 1e-2;
+    let evaluate = |values: &[ArrayD<f32>]| -> f32 {
+        let tensors: Vec<TensorRef> = values.iter().map(|v| Tensor::new(v.clone(), false)).collect();
+        match Tensor::data(&sum(&f(&tensors), None, false)) {
+            TensorData::Scalar(x) => x,
+            TensorData::Tensor(arr) => arr.sum()
+        }
+    };
+
+    let tensors: Vec<TensorRef> = inputs.iter().map(|v| Tensor::new(v.clone(), true)).collect();
+    sum(&f(&tensors), None, false).backward();
+
+    for (i, input) in inputs.iter().enumerate() {
+        let analytic = tensors[i]
+            .borrow()
+            .grad
+            .clone()
+            .map(|g| g.to_array())
+            .unwrap_or_else(|| ArrayD::zeros(input.raw_dim()));
+        let mut numeric = ArrayD::zeros(input.raw_dim());
+
+        for (j, slot) in numeric.iter_mut().enumerate() {
+            let mut plus = inputs.to_vec();
+            let mut minus = inputs.to_vec();
+            plus[i].as_slice_mut().unwrap()[j] += eps;
+            minus[i].as_slice_mut().unwrap()[j] -= eps;
+            *slot = (evaluate(&plus) - evaluate(&minus)) / (2.0 * eps);
+        }
+
+        assert_close(&analytic, &numeric, 2e-2);
+    }
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::{array, grad, gradcheck};
+use nanograd_rs::ops::*;
+use nanograd_rs::tensor::{Tensor, TensorData, TensorOps};
+use ndarray::{array, ArrayD};
+
+#[test]
+fn test_comparisons_return_zero_one_tensors() {
+    let a = Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn(), false);
+    let b = Tensor::new(array![2.0, 2.0, 7.0].into_dyn(), false);
+
+    assert_eq!(array(&eq(&a, &b)), array![[0.0, 1.0, 0.0], [0.0, 0.0, 0.0]].into_dyn());
+    assert_eq!(array(&ne(&a, &b)), array![[1.0, 0.0, 1.0], [1.0, 1.0, 1.0]].into_dyn());
+    assert_eq!(array(&lt(&a, &b)), array![[1.0, 0.0, 1.0], [0.0, 0.0, 1.0]].into_dyn());
+    assert_eq!(array(&le(&a, &b)), array![[1.0, 1.0, 1.0], [0.0, 0.0, 1.0]].into_dyn());
+    assert_eq!(array(&gt(&a, &b)), array![[0.0, 0.0, 0.0], [1.0, 1.0, 0.0]].into_dyn());
+    assert_eq!(array(&ge(&a, &Tensor::new(4.0, false))), array![[0.0, 0.0, 0.0], [1.0, 1.0, 1.0]].into_dyn());
+}
+
+#[test]
+fn test_comparisons_are_not_differentiable() {
+    let a = Tensor::new(array![1.0, 5.0].into_dyn(), true);
+    let b = Tensor::new(array![3.0, 3.0].into_dyn(), true);
+    sum(&mul(&gt(&a, &b), &a), None, false).backward();
+
+    // Only the multiplication contributes: d/da (mask * a) = mask.
+    assert_eq!(grad(&a), array![0.0, 1.0].into_dyn());
+    assert!(b.borrow().grad.is_none());
+
+    let mask = le(&a, &b);
+    let mask = mask.borrow();
+    assert!(!mask.requires_grad && mask.grad_fn.is_none() && mask.parents.is_empty());
+}
+
+#[test]
+fn test_where_broadcasts_and_routes_gradients() {
+    let cond = Tensor::new(array![[1.0], [0.0]].into_dyn(), false);
+    let a = Tensor::new(array![1.0, 2.0, 3.0].into_dyn(), true);
+    let b = Tensor::new(-1.0, true);
+
+    let out = where_(&cond, &a, &b);
+    assert_eq!(array(&out), array![[1.0, 2.0, 3.0], [-1.0, -1.0, -1.0]].into_dyn());
+
+    sum(&out, None, false).backward();
+    assert_eq!(grad(&a), array![1.0, 1.0, 1.0].into_dyn());
+    assert!(&b.borrow().grad.clone().unwrap() == &TensorData::Scalar(3.0));
+}
+
+#[test]
+fn test_where_builds_piecewise_functions() {
+    // |x| written as where(x > 0, x, -x).
+    let values: ArrayD<f32> = array![-1.5, -0.2, 0.7, 2.0].into_dyn();
+    gradcheck(|t| {
+        let zero = Tensor::new(0.0, false);
+        where_(&gt(&t[0], &zero), &t[0], &neg(&t[0]))
+    }, &[values]);
+}
+
+#[test]
+fn test_masked_fill() {
+    let x = Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn(), true);
+    let mask = Tensor::new(array![1.0, 0.0, 1.0].into_dyn(), false);
+
+    let out = masked_fill(&x, &mask, f32::NEG_INFINITY);
+    assert_eq!(array(&out), array![[f32::NEG_INFINITY, 2.0, f32::NEG_INFINITY], [f32::NEG_INFINITY, 5.0, f32::NEG_INFINITY]].into_dyn());
+
+    sum(&softmax(&out, 1), None, false).backward();
+    assert_eq!(array(&softmax(&out, 1)), array![[0.0, 1.0, 0.0], [0.0, 1.0, 0.0]].into_dyn());
+    assert!(grad(&x).iter().all(|g| g.is_finite()));
+    assert_eq!(grad(&x)[[0, 0]], 0.0);
+}
+
+#[test]
+#[should_panic(expected = "does not broadcast")]
+fn test_masked_fill_rejects_growing_mask() {
+    let x = Tensor::new(array![1.0, 2.0].into_dyn(), false);
+    let mask = Tensor::new(array![[1.0, 0.0], [0.0, 1.0]].into_dyn(), false);
+    masked_fill(&x, &mask, 0.0);
+}
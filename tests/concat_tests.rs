@@ -0,0 +1,115 @@
+mod common;
+
+use common::{arange, array, grad, gradcheck};
+use nanograd_rs::ops::{cat, stack, split, chunk, mul, sum};
+use nanograd_rs::ops::op_defs::MultiOp;
+use nanograd_rs::tensor::{self, Tensor, TensorData, TensorOps, TensorRef};
+use ndarray::{arr1, arr2};
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn test_cat_routes_gradient_slices() {
+    let a = Tensor::new(arr2(&[[1.0, 2.0]]).into_dyn(), true);
+    let b = Tensor::new(arr2(&[[3.0, 4.0], [5.0, 6.0]]).into_dyn(), true);
+    let weights = Tensor::new(arr2(&[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]).into_dyn(), false);
+
+    let y = cat(&[a.clone(), b.clone()], 0);
+    assert_eq!(array(&y), arr2(&[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]).into_dyn());
+
+    sum(&mul(&y, &weights), None, false).backward();
+    assert_eq!(grad(&a), arr2(&[[1.0, 2.0]]).into_dyn());
+    assert_eq!(grad(&b), arr2(&[[3.0, 4.0], [5.0, 6.0]]).into_dyn());
+}
+
+#[test]
+fn test_stack_gradcheck() {
+    let y = stack(&[Tensor::new(arange(&[2, 3]), false), Tensor::new(arange(&[2, 3]), false)], 1);
+    assert_eq!(array(&y).shape(), &[2, 2, 3]);
+
+    gradcheck(
+        |t| mul(&stack(&[t[0].clone(), t[1].clone()], 2), &stack(&[t[1].clone(), t[0].clone()], 2)),
+        &[arange(&[2, 3]), arange(&[2, 3]) - 3.0]
+    );
+}
+
+#[test]
+fn test_split_shares_backward() {
+    let x = Tensor::new(arr1(&[1.0, 2.0, 3.0, 4.0, 5.0]).into_dyn(), true);
+    let parts = split(&x, &[2, 3], 0);
+
+    assert_eq!(parts.len(), 2);
+    assert_eq!(array(&parts[0]), arr1(&[1.0, 2.0]).into_dyn());
+    assert_eq!(array(&parts[1]), arr1(&[3.0, 4.0, 5.0]).into_dyn());
+
+    let loss = mul(&sum(&parts[0], None, false), &sum(&mul(&parts[1], &parts[1]), None, false));
+    loss.backward();
+    // d/dx0 = sum(p1^2) = 50, d/dx1 = 2 * p1 * sum(p0) = 6 * p1
+    assert_eq!(grad(&x), arr1(&[50.0, 50.0, 18.0, 24.0, 30.0]).into_dyn());
+}
+
+#[test]
+fn test_chunk_uneven_and_unused_outputs() {
+    let x = Tensor::new(arange(&[2, 5]), true);
+    let chunks = chunk(&x, 3, 1);
+
+    let shapes: Vec<Vec<usize>> = chunks.iter().map(|c| array(c).shape().to_vec()).collect();
+    assert_eq!(shapes, vec![vec![2, 2], vec![2, 2], vec![2, 1]]);
+
+    sum(&chunks[1], None, false).backward();
+    assert_eq!(grad(&x), arr2(&[[0.0, 0.0, 1.0, 1.0, 0.0], [0.0, 0.0, 1.0, 1.0, 0.0]]).into_dyn());
+
+    gradcheck(|t| {
+        let c = chunk(&t[0], 2, 0);
+        mul(&c[0], &c[1])
+    }, &[arange(&[4, 3]) / 4.0]);
+}
+
+#[test]
+fn test_split_in_lazy_mode() {
+    let x = Tensor::new(arange(&[6]), true);
+    let parts = tensor::lazy(|| split(&x, &[1, 2, 3], 0));
+
+    assert!(parts.iter().all(|p| !Tensor::is_realized(p)));
+    assert_eq!(array(&parts[2]), arr1(&[3.0, 4.0, 5.0]).into_dyn());
+    assert_eq!(array(&parts[0]), arr1(&[0.0]).into_dyn());
+
+    sum(&parts[2], None, false).backward();
+    assert_eq!(grad(&x), arr1(&[0.0, 0.0, 0.0, 1.0, 1.0, 1.0]).into_dyn());
+}
+
+/// Duplicates its input; counts how often the shared backward runs.
+#[derive(Debug)]
+struct Duplicate {
+    backward_calls: Rc<Cell<usize>>
+}
+
+impl MultiOp for Duplicate {
+    fn num_outputs(&self) -> usize { 2 }
+
+    fn forward(&self, inputs: &[&TensorRef]) -> Vec<TensorData> {
+        let x = inputs[0].borrow().value().clone();
+        vec![x.clone(), x]
+    }
+
+    fn backward(&self, _inputs: &[TensorRef], grad_outputs: &[Option<TensorData>]) -> Vec<TensorData> {
+        self.backward_calls.set(self.backward_calls.get() + 1);
+        let a = grad_outputs[0].clone().unwrap_or(TensorData::Scalar(0.0));
+        let b = grad_outputs[1].clone().unwrap_or(TensorData::Scalar(0.0));
+        vec![&a + &b]
+    }
+}
+
+#[test]
+fn test_multi_op_backward_runs_once() {
+    let calls = Rc::new(Cell::new(0));
+    let x = Tensor::new(3.0, true);
+    let outputs = Tensor::from_multi_op(Rc::new(Duplicate {backward_calls: calls.clone()}), &[&x]);
+
+    // (x * x) * x through two different outputs: d/dx = 3x^2 = 27
+    let y = mul(&mul(&outputs[0], &outputs[1]), &outputs[0]);
+    y.backward();
+
+    assert_eq!(calls.get(), 1);
+    assert!(&x.borrow().grad.clone().unwrap() == &TensorData::Scalar(27.0));
+}
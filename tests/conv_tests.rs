@@ -0,0 +1,159 @@
+mod common;
+
+use common::{scrambled, array, assert_close, gradcheck};
+use nanograd_rs::ops::{conv1d, conv2d, conv3d, conv_transpose1d, conv_transpose2d, ConvOptions};
+use nanograd_rs::tensor::Tensor;
+use ndarray::{ArrayD, IxDyn};
+
+/// Direct definition of a grouped, strided, padded, dilated 2-D convolution.
+fn naive_conv2d(x: &ArrayD<f32>, w: &ArrayD<f32>, b: &ArrayD<f32>, opt: ConvOptions) -> ArrayD<f32> {
+    let (n, h, wd) = (x.shape()[0], x.shape()[2], x.shape()[3]);
+    let (o, cg, kh, kw) = (w.shape()[0], w.shape()[1], w.shape()[2], w.shape()[3]);
+    let og = o / opt.groups;
+    let oh = (h + 2 * opt.padding - opt.dilation * (kh - 1) - 1) / opt.stride + 1;
+    let ow = (wd + 2 * opt.padding - opt.dilation * (kw - 1) - 1) / opt.stride + 1;
+    let mut out = ArrayD::zeros(IxDyn(&[n, o, oh, ow]));
+    for bi in 0..n {
+        for oc in 0..o {
+            let g = oc / og;
+            for i in 0..oh {
+                for j in 0..ow {
+                    let mut acc = b[[oc]];
+                    for ic in 0..cg {
+                        for p in 0..kh {
+                            for q in 0..kw {
+                                let y = (i * opt.stride + p * opt.dilation) as isize - opt.padding as isize;
+                                let z = (j * opt.stride + q * opt.dilation) as isize - opt.padding as isize;
+                                if y >= 0 && z >= 0 && (y as usize) < h && (z as usize) < wd {
+                                    acc += x[[bi, g * cg + ic, y as usize, z as usize]] * w[[oc, ic, p, q]];
+                                }
+                            }
+                        }
+                    }
+                    out[[bi, oc, i, j]] = acc;
+                }
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn test_conv2d_matches_direct_definition() {
+    let x = scrambled(&[2, 4, 7, 6], 11);
+    let w = scrambled(&[6, 2, 3, 2], 11);
+    let b = scrambled(&[6], 11);
+    let opt = ConvOptions { stride: 2, padding: 1, dilation: 2, groups: 2 };
+
+    let out = conv2d(&Tensor::new(x.clone(), false), &Tensor::new(w.clone(), false), Some(&Tensor::new(b.clone(), false)), opt);
+    assert_close(&array(&out), &naive_conv2d(&x, &w, &b, opt), 1e-5);
+}
+
+#[test]
+fn test_conv1d_output_shape() {
+    let x = Tensor::new(scrambled(&[1, 3, 10], 11), false);
+    let w = Tensor::new(scrambled(&[5, 3, 3], 11), false);
+
+    assert_eq!(array(&conv1d(&x, &w, None, ConvOptions::default())).shape(), &[1, 5, 8]);
+    assert_eq!(array(&conv1d(&x, &w, None, ConvOptions { stride: 3, padding: 2, ..Default::default() })).shape(), &[1, 5, 4]);
+}
+
+#[test]
+fn test_conv_gradcheck() {
+    gradcheck(
+        |t| conv1d(&t[0], &t[1], Some(&t[2]), ConvOptions { stride: 2, padding: 1, dilation: 1, groups: 1 }),
+        &[scrambled(&[2, 2, 7], 11), scrambled(&[3, 2, 3], 11), scrambled(&[3], 11)]
+    );
+    gradcheck(
+        |t| conv2d(&t[0], &t[1], Some(&t[2]), ConvOptions { stride: 1, padding: 1, dilation: 2, groups: 2 }),
+        &[scrambled(&[1, 4, 5, 5], 11), scrambled(&[2, 2, 2, 3], 11), scrambled(&[2], 11)]
+    );
+    gradcheck(
+        |t| conv3d(&t[0], &t[1], None, ConvOptions { stride: 2, padding: 1, ..Default::default() }),
+        &[scrambled(&[1, 1, 4, 3, 4], 11), scrambled(&[2, 1, 2, 2, 2], 11)]
+    );
+}
+
+#[test]
+#[should_panic(expected = "not divisible by groups")]
+fn test_conv_rejects_bad_groups() {
+    conv2d(
+        &Tensor::new(scrambled(&[1, 3, 4, 4], 11), false),
+        &Tensor::new(scrambled(&[2, 1, 2, 2], 11), false),
+        None,
+        ConvOptions { groups: 2, ..Default::default() }
+    );
+}
+
+fn dot(a: &ArrayD<f32>, b: &ArrayD<f32>) -> f32 {
+    a.iter().zip(b.iter()).map(|(x, y)| x * y).sum()
+}
+
+#[test]
+fn test_conv_transpose_is_adjoint_of_conv() {
+    // <conv(x), y> == <x, conv_transpose(y)> for the same weight and options.
+    let opt = ConvOptions { stride: 2, padding: 1, dilation: 2, groups: 2 };
+    let x = scrambled(&[2, 4, 8, 8], 11);
+    let w = Tensor::new(scrambled(&[6, 2, 3, 2], 11), false);
+
+    let conv = array(&conv2d(&Tensor::new(x.clone(), false), &w, None, opt));
+    let y = scrambled(conv.shape(), 11).mapv(|v| v * 0.5 + 0.1);
+    // Recover the trailing row and column the strided conv never reaches.
+    let transposed = array(&conv_transpose2d(&Tensor::new(y.clone(), false), &w, None, opt, 1));
+    assert_eq!(transposed.shape(), x.shape());
+    assert!((dot(&conv, &y) - dot(&x, &transposed)).abs() < 1e-3);
+}
+
+#[test]
+fn test_conv_transpose_output_shape() {
+    let x = Tensor::new(scrambled(&[1, 4, 5], 11), false);
+    let w = Tensor::new(scrambled(&[4, 3, 3], 11), false);
+
+    assert_eq!(array(&conv_transpose1d(&x, &w, None, ConvOptions::default(), 0)).shape(), &[1, 3, 7]);
+    let opt = ConvOptions { stride: 3, padding: 1, dilation: 2, groups: 1 };
+    assert_eq!(array(&conv_transpose1d(&x, &w, None, opt, 2)).shape(), &[1, 3, 17]);
+}
+
+#[test]
+fn test_conv_transpose_output_padding_below_dilation() {
+    // stride 1 with dilation 3: output_padding 2 appends two positions that only see the bias.
+    let x = Tensor::new(ndarray::array![[[1.0, 2.0]]].into_dyn(), false);
+    let w = Tensor::new(ndarray::array![[[3.0, 4.0]]].into_dyn(), false);
+    let b = Tensor::new(ndarray::array![0.5].into_dyn(), false);
+    let opt = ConvOptions { dilation: 3, ..Default::default() };
+
+    let out = conv_transpose1d(&x, &w, Some(&b), opt, 2);
+    assert_eq!(array(&out), ndarray::array![[[3.5, 6.5, 0.5, 4.5, 8.5, 0.5, 0.5]]].into_dyn());
+}
+
+#[test]
+fn test_conv_transpose_gradcheck() {
+    gradcheck(
+        |t| conv_transpose1d(&t[0], &t[1], Some(&t[2]), ConvOptions { stride: 2, padding: 1, ..Default::default() }, 1),
+        &[scrambled(&[2, 2, 4], 11), scrambled(&[2, 3, 3], 11), scrambled(&[3], 11)]
+    );
+    gradcheck(
+        |t| conv_transpose2d(&t[0], &t[1], Some(&t[2]), ConvOptions { stride: 2, padding: 0, dilation: 2, groups: 2 }, 1),
+        &[scrambled(&[1, 4, 3, 2], 11), scrambled(&[4, 1, 2, 3], 11), scrambled(&[2], 11)]
+    );
+    gradcheck(
+        |t| conv_transpose1d(&t[0], &t[1], Some(&t[2]), ConvOptions { padding: 1, dilation: 3, ..Default::default() }, 2),
+        &[scrambled(&[2, 2, 4], 11), scrambled(&[2, 3, 2], 11), scrambled(&[3], 11)]
+    );
+    gradcheck(
+        |t| conv_transpose2d(&t[0], &t[1], Some(&t[2]), ConvOptions { dilation: 2, ..Default::default() }, 1),
+        &[scrambled(&[1, 2, 3, 2], 11), scrambled(&[2, 3, 2, 2], 11), scrambled(&[3], 11)]
+    );
+}
+
+#[test]
+#[should_panic(expected = "must be smaller than stride")]
+fn test_conv_transpose_rejects_large_output_padding() {
+    conv_transpose1d(
+        &Tensor::new(scrambled(&[1, 2, 4], 11), false),
+        &Tensor::new(scrambled(&[2, 2, 3], 11), false),
+        None,
+        ConvOptions { stride: 2, ..Default::default() },
+        2
+    );
+}
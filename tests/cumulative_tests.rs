@@ -0,0 +1,79 @@
+mod common;
+
+use common::{array, assert_close, grad, gradcheck};
+use nanograd_rs::ops::*;
+use nanograd_rs::tensor::{Tensor, TensorOps};
+use ndarray::{array, ArrayD};
+
+fn values() -> ArrayD<f32> {
+    array![[0.5, -1.2, 2.0, 1.4], [0.3, -0.7, 0.9, -1.6]].into_dyn()
+}
+
+#[test]
+fn test_cumulative_values() {
+    let x = Tensor::new(array![[1.0, 3.0, 2.0], [-1.0, 4.0, -2.0]].into_dyn(), false);
+
+    assert_eq!(array(&cumsum(&x, 1)), array![[1.0, 4.0, 6.0], [-1.0, 3.0, 1.0]].into_dyn());
+    assert_eq!(array(&cumsum(&x, 0)), array![[1.0, 3.0, 2.0], [0.0, 7.0, 0.0]].into_dyn());
+    assert_eq!(array(&cumprod(&x, -1)), array![[1.0, 3.0, 6.0], [-1.0, -4.0, 8.0]].into_dyn());
+    assert_eq!(array(&cummax(&x, 1)), array![[1.0, 3.0, 3.0], [-1.0, 4.0, 4.0]].into_dyn());
+    assert_eq!(array(&cummin(&x, 1)), array![[1.0, 1.0, 1.0], [-1.0, -1.0, -2.0]].into_dyn());
+
+    let expected = array(&x).mapv(f32::exp);
+    let mut running = expected.clone();
+    for mut row in running.rows_mut() {
+        let mut acc = 0.0;
+        for v in row.iter_mut() {
+            acc += *v;
+            *v = acc.ln();
+        }
+    }
+    assert_close(&array(&logcumsumexp(&x, 1)), &running, 1e-6);
+}
+
+#[test]
+fn test_cumsum_backward_is_reverse_cumsum() {
+    let x = Tensor::new(array![1.0, 2.0, 3.0, 4.0].into_dyn(), true);
+    let weights = Tensor::new(array![1.0, 10.0, 100.0, 1000.0].into_dyn(), false);
+    sum(&mul(&cumsum(&x, 0), &weights), None, false).backward();
+    assert_eq!(grad(&x), array![1111.0, 1110.0, 1100.0, 1000.0].into_dyn());
+}
+
+#[test]
+fn test_cumprod_gradient_with_zeros() {
+    let x = Tensor::new(array![2.0, 0.0, 3.0, 0.0, 5.0].into_dyn(), true);
+    sum(&cumprod(&x, 0), None, false).backward();
+    // y = [2, 0, 0, 0, 0]; only the first zero still influences later outputs.
+    assert_eq!(grad(&x), array![1.0, 2.0 + 6.0, 0.0, 0.0, 0.0].into_dyn());
+
+    let x = Tensor::new(array![0.0, 4.0, 0.5].into_dyn(), true);
+    sum(&cumprod(&x, 0), None, false).backward();
+    assert_eq!(grad(&x), array![1.0 + 4.0 + 2.0, 0.0, 0.0].into_dyn());
+}
+
+#[test]
+fn test_cummax_routes_gradient_to_running_max() {
+    let x = Tensor::new(array![1.0, 3.0, 2.0, 3.0, 0.0].into_dyn(), true);
+    sum(&cummax(&x, 0), None, false).backward();
+    assert_eq!(grad(&x), array![1.0, 2.0, 0.0, 2.0, 0.0].into_dyn());
+}
+
+#[test]
+fn test_logcumsumexp_is_stable() {
+    let x = Tensor::new(array![1000.0, 1000.0, -1000.0, 1001.0].into_dyn(), true);
+    let y = logcumsumexp(&x, 0);
+    assert!(array(&y).iter().all(|v| v.is_finite()));
+    assert_close(&array(&y).slice(ndarray::s![..2]).to_owned().into_dyn(), &array![1000.0, 1000.0 + 2.0f32.ln()].into_dyn(), 1e-6);
+
+    sum(&y, None, false).backward();
+    assert!(grad(&x).iter().all(|v| v.is_finite()));
+}
+
+#[test]
+fn test_cumulative_gradcheck() {
+    let weights = array![[1.0, -2.0, 0.5, 3.0], [0.2, 1.5, -1.0, 2.0]].into_dyn();
+    for op in [cumsum, cumprod, cummax, cummin, logcumsumexp] {
+        gradcheck(|t| mul(&op(&t[0], 1), &t[1]), &[values(), weights.clone()]);
+        gradcheck(|t| mul(&op(&t[0], 0), &t[1]), &[values(), weights.clone()]);
+    }
+}
@@ -0,0 +1,62 @@
+use nanograd_rs::tensor;
+use nanograd_rs::tensor::{Tensor, TensorData};
+use ndarray::{arr1, arr2, ArrayD};
+
+fn array(t: &nanograd_rs::tensor::TensorRef) -> ArrayD<f32> {
+    match Tensor::data(t) {
+        TensorData::Tensor(arr) => arr,
+        TensorData::Scalar(_) => panic!("expected tensor")
+    }
+}
+
+#[test]
+fn test_constant_factories() {
+    let z = Tensor::zeros(&[2, 3], true);
+    let o = Tensor::ones_like(&z, false);
+    let f = Tensor::full(&[4], 2.5, false);
+
+    assert_eq!(array(&z), ArrayD::zeros(vec![2, 3]));
+    assert!(z.borrow().requires_grad);
+    assert_eq!(array(&o), ArrayD::ones(vec![2, 3]));
+    assert!(!o.borrow().requires_grad);
+    assert_eq!(array(&f), arr1(&[2.5, 2.5, 2.5, 2.5]).into_dyn());
+    assert_eq!(array(&Tensor::zeros_like(&f, false)), ArrayD::zeros(vec![4]));
+}
+
+#[test]
+fn test_ranges_and_eye() {
+    assert_eq!(array(&Tensor::arange(0.0, 2.0, 0.5, false)), arr1(&[0.0, 0.5, 1.0, 1.5]).into_dyn());
+    assert_eq!(array(&Tensor::arange(3.0, 0.0, -1.0, false)), arr1(&[3.0, 2.0, 1.0]).into_dyn());
+    assert_eq!(array(&Tensor::linspace(-1.0, 1.0, 5, false)), arr1(&[-1.0, -0.5, 0.0, 0.5, 1.0]).into_dyn());
+    assert_eq!(array(&Tensor::eye(2, false)), arr2(&[[1.0, 0.0], [0.0, 1.0]]).into_dyn());
+}
+
+#[test]
+fn test_from_vec() {
+    let t = Tensor::from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[3, 2], false);
+    assert_eq!(array(&t), arr2(&[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]).into_dyn());
+}
+
+#[test]
+#[should_panic(expected = "Cannot build tensor of shape [2, 2]")]
+fn test_from_vec_rejects_wrong_length() {
+    Tensor::from_vec(vec![1.0, 2.0, 3.0], &[2, 2], false);
+}
+
+#[test]
+fn test_tensor_macro() {
+    let m = tensor!([[1.0, -2.0], [3, 4]], true);
+    assert_eq!(array(&m), arr2(&[[1.0, -2.0], [3.0, 4.0]]).into_dyn());
+    assert!(m.borrow().requires_grad);
+
+    let cube = tensor!([[[1.0], [2.0]], [[3.0], [4.0]]]);
+    assert_eq!(array(&cube).shape(), &[2, 2, 1]);
+
+    assert!(&Tensor::data(&tensor!(1.5)) == &TensorData::Scalar(1.5));
+}
+
+#[test]
+#[should_panic(expected = "Ragged tensor literal")]
+fn test_tensor_macro_rejects_ragged() {
+    tensor!([[1.0, 2.0], [3.0]]);
+}
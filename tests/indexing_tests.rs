@@ -0,0 +1,148 @@
+mod common;
+
+use common::{array, grad, gradcheck};
+use nanograd_rs::ops::{slice, index, narrow, gather, scatter_add, index_select, index_add, sum, mul};
+use nanograd_rs::tensor::{Tensor, TensorData, TensorOps};
+use ndarray::{arr1, arr2, Array, IxDyn, Slice};
+
+#[test]
+fn test_slice_with_steps() {
+    let x = Tensor::new(Array::range(0.0, 12.0, 1.0).into_shape_with_order(IxDyn(&[3, 4])).unwrap(), true);
+    let y = slice(&x, &[Slice::from(1..), Slice::new(0, None, 2)]);
+
+    assert_eq!(array(&y), arr2(&[[4.0, 6.0], [8.0, 10.0]]).into_dyn());
+
+    sum(&y, None, false).backward();
+    assert_eq!(grad(&x), arr2(&[[0.0, 0.0, 0.0, 0.0], [1.0, 0.0, 1.0, 0.0], [1.0, 0.0, 1.0, 0.0]]).into_dyn());
+}
+
+#[test]
+fn test_slice_negative_bounds() {
+    let x = Tensor::new(arr1(&[1.0, 2.0, 3.0, 4.0]).into_dyn(), false);
+    let y = slice(&x, &[Slice::new(-3, Some(-1), 1)]);
+
+    assert_eq!(array(&y), arr1(&[2.0, 3.0]).into_dyn());
+}
+
+#[test]
+fn test_index_along_axis() {
+    let x = Tensor::new(arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn(), true);
+    let col = index(&x, 1, 2);
+    let row = index(&x, 0, 1);
+
+    assert_eq!(array(&col), arr1(&[3.0, 6.0]).into_dyn());
+    assert!(&Tensor::data(&index(&row, 0, 0)) == &TensorData::Scalar(4.0));
+
+    // col = x[:, 2] and row = x[1] overlap only at x[1, 2], which collects both gradients
+    sum(&col, None, false).backward();
+    sum(&row, None, false).backward();
+    assert_eq!(grad(&x), arr2(&[[0.0, 0.0, 1.0], [1.0, 1.0, 2.0]]).into_dyn());
+}
+
+#[test]
+#[should_panic(expected = "Index 3 out of range")]
+fn test_index_out_of_range() {
+    let x = Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false);
+    index(&x, 0, 3);
+}
+
+#[test]
+fn test_narrow_gradcheck() {
+    let x = Array::range(0.0, 24.0, 1.0).into_shape_with_order(IxDyn(&[2, 3, 4])).unwrap();
+    let y = Tensor::new(x.clone(), false);
+    assert_eq!(array(&narrow(&y, 2, 1, 2)).shape(), &[2, 3, 2]);
+
+    gradcheck(|t| mul(&narrow(&t[0], 1, 1, 2), &narrow(&t[0], 1, 0, 2)), &[x / 10.0]);
+}
+
+#[test]
+#[should_panic(expected = "out of range for axis 0 of size 3")]
+fn test_narrow_past_end() {
+    narrow(&Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false), 0, 2, 2);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn test_slice_rejects_negative_start_past_beginning() {
+    slice(&Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false), &[Slice::new(-5, None, 1)]);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn test_slice_rejects_negative_end_past_beginning() {
+    slice(&Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false), &[Slice::new(0, Some(-4), 1)]);
+}
+
+#[test]
+#[should_panic(expected = "Axis 2 out of range for a 2-d tensor")]
+fn test_narrow_rejects_bad_axis() {
+    narrow(&Tensor::new(arr2(&[[1.0, 2.0], [3.0, 4.0]]).into_dyn(), false), 2, 0, 1);
+}
+
+#[test]
+#[should_panic(expected = "Axis 1 out of range for a 1-d tensor")]
+fn test_index_rejects_bad_axis() {
+    index(&Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false), 1, 0);
+}
+
+#[test]
+#[should_panic(expected = "overflows")]
+fn test_narrow_rejects_overflowing_range() {
+    narrow(&Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false), 0, 1, usize::MAX);
+}
+
+#[test]
+fn test_gather_and_backward() {
+    let x = Tensor::new(arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn(), true);
+    let idx = Tensor::new(arr2(&[[2.0, 0.0], [1.0, 1.0]]).into_dyn(), false);
+    let y = gather(&x, 1, &idx);
+
+    assert_eq!(array(&y), arr2(&[[3.0, 1.0], [5.0, 5.0]]).into_dyn());
+
+    sum(&y, None, false).backward();
+    assert_eq!(grad(&x), arr2(&[[1.0, 0.0, 1.0], [0.0, 2.0, 0.0]]).into_dyn());
+}
+
+#[test]
+fn test_scatter_add_gradcheck() {
+    let idx = Tensor::new(arr2(&[[0.0, 2.0], [0.0, 0.0]]).into_dyn(), false);
+    let x = arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn();
+    let src = arr2(&[[0.5, -1.0, 7.0], [2.0, 3.0, 9.0]]).into_dyn();
+
+    let out = scatter_add(&Tensor::new(x.clone(), false), 1, &idx, &Tensor::new(src.clone(), false));
+    assert_eq!(array(&out), arr2(&[[1.5, 2.0, 2.0], [9.0, 5.0, 6.0]]).into_dyn());
+
+    gradcheck(|t| mul(&scatter_add(&t[0], 1, &idx, &t[1]), &t[0]), &[x, src]);
+}
+
+#[test]
+fn test_index_select_and_index_add() {
+    let x = arr2(&[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]).into_dyn();
+    let idx = Tensor::new(arr1(&[2.0, 0.0, 2.0]).into_dyn(), false);
+
+    let selected = index_select(&Tensor::new(x.clone(), false), 0, &idx);
+    assert_eq!(array(&selected), arr2(&[[5.0, 6.0], [1.0, 2.0], [5.0, 6.0]]).into_dyn());
+
+    let src = arr2(&[[1.0, 1.0], [2.0, 2.0], [3.0, 3.0]]).into_dyn();
+    let added = index_add(&Tensor::new(x.clone(), false), 0, &idx, &Tensor::new(src.clone(), false));
+    assert_eq!(array(&added), arr2(&[[3.0, 4.0], [3.0, 4.0], [9.0, 10.0]]).into_dyn());
+
+    gradcheck(|t| mul(&index_add(&t[0], 0, &idx, &t[1]), &t[0]), &[x.clone(), src]);
+    gradcheck(|t| mul(&index_select(&t[0], 0, &idx), &t[0]), &[x]);
+}
+
+#[test]
+#[should_panic(expected = "Index 3 out of range for axis of size 3")]
+fn test_gather_rejects_out_of_bounds() {
+    let x = Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false);
+    let idx = Tensor::new(arr1(&[3.0]).into_dyn(), false);
+    gather(&x, 0, &idx);
+}
+
+#[test]
+#[should_panic(expected = "Indices must be non-negative integers")]
+fn test_index_select_rejects_fractional_indices() {
+    let x = Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false);
+    let idx = Tensor::new(arr1(&[0.5]).into_dyn(), false);
+    index_select(&x, 0, &idx);
+}
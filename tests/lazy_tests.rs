@@ -0,0 +1,107 @@
+use nanograd_rs::tensor::{self, Tensor, TensorData, TensorOps, TensorRef};
+use nanograd_rs::ops::{add, sub, mul, sum, exp, relu};
+use ndarray::Array;
+use std::rc::Rc;
+
+#[test]
+fn test_lazy_ops_defer_until_realize() {
+    let a = Tensor::new(2.0, false);
+    let b = Tensor::new(3.0, false);
+
+    let c = tensor::lazy(|| mul(&add(&a, &b), &b));
+    assert!(!Tensor::is_realized(&c));
+
+    c.realize();
+    assert!(Tensor::is_realized(&c));
+    assert_eq!(c.borrow().value(), &15.0.into());
+}
+
+#[test]
+#[should_panic(expected = "not realized")]
+fn test_reading_unrealized_value_panics() {
+    let a = Tensor::new(2.0, false);
+    let b = tensor::lazy(|| add(&a, &a));
+    let _ = b.borrow().value();
+}
+
+#[test]
+fn test_data_access_realizes() {
+    let x = Tensor::new(Array::from_vec(vec![1.0, 2.0, 3.0]).into_dyn(), false);
+    let total = tensor::lazy(|| sum(&mul(&x, &x), None, false));
+
+    assert_eq!(&total.data(), &14.0.into());
+    assert!(Tensor::is_realized(&total));
+}
+
+#[test]
+fn test_unused_outputs_are_skipped() {
+    let x = Tensor::new(4.0, false);
+    let (used, unused) = tensor::lazy(|| {
+        let shared = add(&x, &x);
+        (mul(&shared, &x), sub(&shared, &x))
+    });
+
+    used.realize();
+    assert_eq!(&used.data(), &32.0.into());
+    assert!(!Tensor::is_realized(&unused));
+}
+
+#[test]
+fn test_elementwise_ops_reuse_dead_buffers() {
+    let x = Tensor::new(Array::from_vec(vec![-1.0, 0.5, 2.0]).into_dyn(), false);
+    let buffer = |t: &TensorRef| match t.borrow().value() {
+        TensorData::Tensor(arr) => arr.as_ptr(),
+        TensorData::Scalar(_) => panic!("expected tensor")
+    };
+
+    // exp(relu(x + x)): both unary ops can write into the buffer of x + x.
+    let sum_ptr = std::cell::Cell::new(std::ptr::null());
+    let out = tensor::lazy(|| {
+        let doubled = add(&x, &x);
+        let out = exp(&relu(&doubled));
+        doubled.realize();
+        sum_ptr.set(buffer(&doubled));
+        out
+    });
+    out.realize();
+    assert_eq!(buffer(&out), sum_ptr.get());
+    assert!(&out.data() == &TensorData::Tensor(Array::from_vec(vec![1.0, 1.0f32.exp(), 4.0f32.exp()]).into_dyn()));
+
+    // A buffer that is still referenced is left alone.
+    let y = tensor::lazy(|| exp(&x));
+    y.realize();
+    assert_ne!(buffer(&y), buffer(&x));
+}
+
+#[test]
+fn test_intermediates_released_after_realize() {
+    let x = Tensor::new(1.0, false);
+    let (out, weak) = tensor::lazy(|| {
+        let intermediate = add(&x, &x);
+        let weak = Rc::downgrade(&intermediate);
+        (mul(&intermediate, &x), weak)
+    });
+
+    assert!(weak.upgrade().is_some());
+    out.realize();
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn test_lazy_backward_matches_eager() {
+    let run = || {
+        let x = Tensor::new(Array::from_vec(vec![0.5, -1.0, 2.0]).into_dyn(), true);
+        let w = Tensor::new(3.0, true);
+        let loss = sum(&mul(&sub(&x, &w), &x), None, false);
+        loss.backward();
+        (loss.data(), x.borrow().grad.clone().unwrap(), w.borrow().grad.clone().unwrap())
+    };
+
+    let (eager_loss, eager_gx, eager_gw) = run();
+    let (lazy_loss, lazy_gx, lazy_gw) = tensor::lazy(run);
+
+    assert!(!tensor::is_lazy());
+    assert!(&eager_loss == &lazy_loss);
+    assert!(&eager_gx == &lazy_gx);
+    assert!(&eager_gw == &lazy_gw);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::{arange, array, assert_close, gradcheck};
+use nanograd_rs::backend::{self, ReferenceBackend};
+use nanograd_rs::ops::{matmul, einsum};
+use nanograd_rs::tensor::{Tensor, TensorData};
+use ndarray::{arr1, arr2, ArrayD};
+use std::rc::Rc;
+
+/// `arange` scaled into `[0, 1)`.
+fn unit_range(shape: &[usize]) -> ArrayD<f32> {
+    arange(shape) / shape.iter().product::<usize>() as f32
+}
+
+#[test]
+fn test_matmul_vector_and_matrix_cases() {
+    let v = Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false);
+    let m = Tensor::new(arr2(&[[1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]).into_dyn(), false);
+    let sq = Tensor::new(arr2(&[[1.0, 2.0], [3.0, 4.0]]).into_dyn(), false);
+
+    assert!(&Tensor::data(&matmul(&v, &v)) == &TensorData::Scalar(14.0));
+    assert_eq!(array(&matmul(&v, &m)), arr1(&[4.0, 5.0]).into_dyn());
+    assert_eq!(array(&matmul(&m, &Tensor::new(arr1(&[1.0, -1.0]).into_dyn(), false))), arr1(&[1.0, -1.0, 0.0]).into_dyn());
+    assert_eq!(array(&matmul(&m, &sq)), arr2(&[[1.0, 2.0], [3.0, 4.0], [4.0, 6.0]]).into_dyn());
+}
+
+#[test]
+fn test_batched_matmul_broadcasts() {
+    let a = Tensor::new(unit_range(&[2, 1, 3, 4]), false);
+    let b = Tensor::new(unit_range(&[5, 4, 2]), false);
+    let c = array(&matmul(&a, &b));
+
+    assert_eq!(c.shape(), &[2, 5, 3, 2]);
+    let a_arr = unit_range(&[2, 1, 3, 4]);
+    let b_arr = unit_range(&[5, 4, 2]);
+    let expected: f32 = (0..4).map(|k| a_arr[[1, 0, 2, k]] * b_arr[[3, k, 1]]).sum();
+    assert!((c[[1, 3, 2, 1]] - expected).abs() < 1e-6);
+}
+
+#[test]
+fn test_matmul_gradcheck() {
+    gradcheck(|t| matmul(&t[0], &t[1]), &[unit_range(&[3, 4]), unit_range(&[4, 2]) - 0.5]);
+    gradcheck(|t| matmul(&t[0], &t[1]), &[unit_range(&[4]), unit_range(&[4]) - 0.5]);
+    gradcheck(|t| matmul(&t[0], &t[1]), &[unit_range(&[4]), unit_range(&[2, 4, 3])]);
+    gradcheck(|t| matmul(&t[0], &t[1]), &[unit_range(&[2, 3, 4]), unit_range(&[4])]);
+    gradcheck(|t| matmul(&t[0], &t[1]), &[unit_range(&[2, 1, 3, 4]), unit_range(&[3, 4, 2]) - 0.5]);
+}
+
+#[test]
+fn test_matmul_matches_reference_backend() {
+    let a = unit_range(&[3, 2, 5]);
+    let b = unit_range(&[5, 4]);
+    let fast = array(&matmul(&Tensor::new(a.clone(), false), &Tensor::new(b.clone(), false)));
+    let slow = backend::with_backend(Rc::new(ReferenceBackend), || {
+        array(&matmul(&Tensor::new(a, false), &Tensor::new(b, false)))
+    });
+
+    assert_close(&fast, &slow, 1e-6);
+}
+
+#[test]
+#[should_panic(expected = "mismatched inner dimensions")]
+fn test_matmul_rejects_mismatched_shapes() {
+    matmul(&Tensor::new(unit_range(&[2, 3]), false), &Tensor::new(unit_range(&[2, 3]), false));
+}
+
+#[test]
+fn test_einsum_matches_matmul() {
+    let a = Tensor::new(unit_range(&[2, 3, 4]), false);
+    let b = Tensor::new(unit_range(&[2, 4, 5]), false);
+
+    assert_close(&array(&einsum("bij,bjk->bik", &[a.clone(), b.clone()])), &array(&matmul(&a, &b)), 1e-6);
+}
+
+#[test]
+fn test_einsum_forms() {
+    let u = Tensor::new(arr1(&[1.0, 2.0]).into_dyn(), false);
+    let v = Tensor::new(arr1(&[3.0, 4.0, 5.0]).into_dyn(), false);
+    let m = Tensor::new(arr2(&[[1.0, 2.0], [3.0, 4.0]]).into_dyn(), false);
+
+    assert_eq!(array(&einsum("i,j->ij", &[u.clone(), v])), arr2(&[[3.0, 4.0, 5.0], [6.0, 8.0, 10.0]]).into_dyn());
+    assert!(&Tensor::data(&einsum("ii", std::slice::from_ref(&m))) == &TensorData::Scalar(5.0));
+    assert_eq!(array(&einsum("ii->i", std::slice::from_ref(&m))), arr1(&[1.0, 4.0]).into_dyn());
+    // implicit output: letters appearing once, alphabetically
+    assert_eq!(array(&einsum("ij", std::slice::from_ref(&m))), arr2(&[[1.0, 2.0], [3.0, 4.0]]).into_dyn());
+    assert_eq!(array(&einsum("ji", std::slice::from_ref(&m))), arr2(&[[1.0, 3.0], [2.0, 4.0]]).into_dyn());
+    assert_eq!(array(&einsum("i, ij -> j", &[u, m])), arr1(&[7.0, 10.0]).into_dyn());
+}
+
+#[test]
+fn test_einsum_gradcheck() {
+    gradcheck(|t| einsum("bij,bjk->bik", &[t[0].clone(), t[1].clone()]), &[unit_range(&[2, 3, 4]), unit_range(&[2, 4, 2]) - 0.5]);
+    gradcheck(|t| einsum("ij,j,k->ik", &[t[0].clone(), t[1].clone(), t[2].clone()]), &[unit_range(&[2, 3]), unit_range(&[3]), unit_range(&[2]) + 1.0]);
+    gradcheck(|t| einsum("ii->i", &[t[0].clone()]), &[unit_range(&[3, 3])]);
+    gradcheck(|t| einsum("ij->i", &[t[0].clone()]), &[unit_range(&[2, 3])]);
+}
+
+#[test]
+#[should_panic(expected = "inconsistent sizes")]
+fn test_einsum_rejects_mismatched_dims() {
+    einsum("ij,jk->ik", &[Tensor::new(unit_range(&[2, 3]), false), Tensor::new(unit_range(&[4, 2]), false)]);
+}
+
+#[test]
+#[should_panic(expected = "does not appear in the inputs")]
+fn test_einsum_rejects_unknown_output() {
+    einsum("ij->ik", &[Tensor::new(unit_range(&[2, 3]), false)]);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::{scrambled, array, assert_close, grad, gradcheck};
+use nanograd_rs::ops::{
+    adaptive_avg_pool2d, adaptive_max_pool2d, avg_pool1d, avg_pool2d, max_pool1d, max_pool2d, mul, sum, PoolOptions
+};
+use nanograd_rs::tensor::{Tensor, TensorOps};
+use ndarray::array;
+
+#[test]
+fn test_max_pool2d_forward_and_backward() {
+    let x = Tensor::new(array![[[
+        [1.0, 5.0, 2.0, 0.0],
+        [3.0, 4.0, 8.0, 1.0],
+        [0.0, 2.0, 1.0, 1.0],
+        [7.0, 1.0, 3.0, 6.0]
+    ]]].into_dyn(), true);
+    let out = max_pool2d(&x, PoolOptions::new(2));
+    assert_eq!(array(&out), array![[[[5.0, 8.0], [7.0, 6.0]]]].into_dyn());
+
+    let weights = Tensor::new(array![[[[1.0, 2.0], [3.0, 4.0]]]].into_dyn(), false);
+    sum(&mul(&out, &weights), None, false).backward();
+    let expected = array![[[
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 2.0, 0.0],
+        [0.0, 0.0, 0.0, 0.0],
+        [3.0, 0.0, 0.0, 4.0]
+    ]]].into_dyn();
+    assert_eq!(grad(&x), expected);
+}
+
+#[test]
+fn test_max_pool_propagates_nan() {
+    let x = Tensor::new(array![[[1.0, f32::NAN, 3.0, 2.0]]].into_dyn(), true);
+    let out = array(&max_pool1d(&x, PoolOptions::new(2)));
+    assert!(out[[0, 0, 0]].is_nan());
+    assert_eq!(out[[0, 0, 1]], 3.0);
+
+    sum(&max_pool1d(&x, PoolOptions::new(2)), None, false).backward();
+    assert_eq!(grad(&x), array![[[0.0, 1.0, 1.0, 0.0]]].into_dyn());
+}
+
+#[test]
+fn test_max_pool1d_padding_and_ceil_mode() {
+    let x = Tensor::new(array![[[-1.0, -3.0, -2.0, -5.0, -4.0]]].into_dyn(), false);
+
+    // Padded positions never win, even against negative inputs.
+    let padded = max_pool1d(&x, PoolOptions { kernel: 2, stride: 2, padding: 1, ceil_mode: false });
+    assert_eq!(array(&padded), array![[[-1.0, -2.0, -4.0]]].into_dyn());
+
+    let floor = max_pool1d(&x, PoolOptions::new(2));
+    let ceil = max_pool1d(&x, PoolOptions { ceil_mode: true, ..PoolOptions::new(2) });
+    assert_eq!(array(&floor), array![[[-1.0, -2.0]]].into_dyn());
+    assert_eq!(array(&ceil), array![[[-1.0, -2.0, -4.0]]].into_dyn());
+}
+
+#[test]
+fn test_avg_pool_counts_padding() {
+    let x = Tensor::new(array![[[1.0, 2.0, 3.0, 4.0]]].into_dyn(), true);
+    let out = avg_pool1d(&x, PoolOptions { kernel: 3, stride: 2, padding: 1, ceil_mode: false });
+    assert_close(&array(&out), &array![[[1.0, 3.0]]].into_dyn(), 1e-6);
+
+    sum(&out, None, false).backward();
+    assert_close(&grad(&x), &array![[[1.0 / 3.0, 2.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0]]].into_dyn(), 1e-6);
+}
+
+#[test]
+fn test_adaptive_pools() {
+    let x = Tensor::new(scrambled(&[2, 3, 6, 4], 13), false);
+
+    // Divisible sizes reduce to ordinary pooling.
+    assert_close(&array(&adaptive_avg_pool2d(&x, [3, 2])), &array(&avg_pool2d(&x, PoolOptions::new(2))), 1e-6);
+    assert_eq!(array(&adaptive_max_pool2d(&x, [3, 2])), array(&max_pool2d(&x, PoolOptions::new(2))));
+
+    // Otherwise windows overlap: rows [0, 3) and [2, 5) of a 5-row input.
+    let x = Tensor::new(array![[[[1.0], [2.0], [3.0], [4.0], [5.0]]]].into_dyn(), false);
+    assert_close(&array(&adaptive_avg_pool2d(&x, [2, 1])), &array![[[[2.0], [4.0]]]].into_dyn(), 1e-6);
+    assert_eq!(array(&adaptive_max_pool2d(&x, [2, 1])), array![[[[3.0], [5.0]]]].into_dyn());
+}
+
+#[test]
+fn test_pool_gradcheck() {
+    let opt = PoolOptions { kernel: 3, stride: 2, padding: 1, ceil_mode: true };
+    gradcheck(|t| max_pool2d(&t[0], opt), &[scrambled(&[1, 2, 5, 4], 13)]);
+    gradcheck(|t| mul(&avg_pool2d(&t[0], opt), &t[1]), &[scrambled(&[1, 2, 5, 4], 13), scrambled(&[1, 2, 3, 3], 13)]);
+    gradcheck(|t| mul(&adaptive_avg_pool2d(&t[0], [2, 3]), &t[1]), &[scrambled(&[1, 1, 5, 4], 13), scrambled(&[1, 1, 2, 3], 13)]);
+}
+
+#[test]
+#[should_panic(expected = "at most half of kernel size")]
+fn test_pool_rejects_large_padding() {
+    max_pool1d(&Tensor::new(scrambled(&[1, 1, 6], 13), false), PoolOptions { padding: 2, ..PoolOptions::new(3) });
+}
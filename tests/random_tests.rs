@@ -0,0 +1,77 @@
+use nanograd_rs::random::{self, Generator};
+use nanograd_rs::tensor::{Tensor, TensorData, TensorRef};
+
+fn values(t: &TensorRef) -> Vec<f32> {
+    match Tensor::data(t) {
+        TensorData::Tensor(arr) => arr.iter().copied().collect(),
+        TensorData::Scalar(x) => vec![x]
+    }
+}
+
+#[test]
+fn test_same_seed_same_values() {
+    let mut a = Generator::new(42);
+    let mut b = Generator::new(42);
+    let mut c = Generator::new(43);
+
+    let (ra, rb, rc) = (a.randn(&[16], false), b.randn(&[16], false), c.randn(&[16], false));
+    assert_eq!(values(&ra), values(&rb));
+    assert_ne!(values(&ra), values(&rc));
+    assert_eq!(values(&a.rand(&[3, 3], false)), values(&b.rand(&[3, 3], false)));
+}
+
+#[test]
+fn test_manual_seed_resets_global_generator() {
+    random::manual_seed(7);
+    let first = (values(&random::rand(&[5], false)), values(&random::randperm(10)));
+    random::manual_seed(7);
+    let second = (values(&random::rand(&[5], false)), values(&random::randperm(10)));
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_distribution_ranges() {
+    let mut g = Generator::new(0);
+
+    let uniform = values(&g.rand(&[1000], true));
+    assert!(uniform.iter().all(|&x| (0.0..1.0).contains(&x)));
+
+    let normal = values(&g.randn(&[10000], false));
+    let mean = normal.iter().sum::<f32>() / normal.len() as f32;
+    let var = normal.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / normal.len() as f32;
+    assert!(mean.abs() < 0.05);
+    assert!((var - 1.0).abs() < 0.05);
+
+    let ints = values(&g.randint(-2, 3, &[1000]));
+    assert!(ints.iter().all(|&x| x.fract() == 0.0 && (-2.0..3.0).contains(&x)));
+    assert!(ints.contains(&-2.0) && ints.contains(&2.0));
+}
+
+#[test]
+fn test_randint_spans_the_full_i64_range() {
+    let mut g = Generator::new(3);
+    let ints = values(&g.randint(i64::MIN, i64::MAX, &[1000]));
+    assert!(ints.iter().any(|&x| x < 0.0) && ints.iter().any(|&x| x > 0.0));
+
+    let top = values(&g.randint(i64::MAX - 1, i64::MAX, &[10]));
+    assert!(top.iter().all(|&x| x == (i64::MAX - 1) as f32));
+}
+
+#[test]
+#[should_panic(expected = "randint expects low < high, got [5, 5)")]
+fn test_randint_rejects_empty_range() {
+    Generator::new(0).randint(5, 5, &[3]);
+}
+
+#[test]
+fn test_bernoulli_and_randperm() {
+    let mut g = Generator::new(1);
+
+    let probs = Tensor::from_vec(vec![0.0, 1.0, 0.0, 1.0], &[2, 2], false);
+    assert_eq!(values(&g.bernoulli(&probs)), vec![0.0, 1.0, 0.0, 1.0]);
+
+    let mut perm = values(&g.randperm(8));
+    perm.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(perm, (0..8).map(|i| i as f32).collect::<Vec<_>>());
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use common::{array, assert_close, grad, gradcheck};
+use nanograd_rs::ops::*;
+use nanograd_rs::tensor::{Tensor, TensorData, TensorOps, TensorRef};
+use ndarray::{array, ArrayD};
+
+fn values() -> ArrayD<f32> {
+    array![[[0.5, -1.2, 2.0], [1.4, 0.3, -0.7]], [[-0.4, 2.2, 1.1], [0.9, -1.6, 0.2]]].into_dyn()
+}
+
+#[test]
+fn test_max_min_values_and_shapes() {
+    let x = Tensor::new(values(), false);
+
+    assert!(&Tensor::data(&max(&x, None, false)) == &TensorData::Scalar(2.2));
+    assert!(&Tensor::data(&min(&x, None, false)) == &TensorData::Scalar(-1.6));
+    assert_eq!(array(&max(&x, Some(vec![2]), false)), array![[2.0, 1.4], [2.2, 0.9]].into_dyn());
+    assert_eq!(array(&min(&x, Some(vec![0, 2]), true)), array![[[-1.2], [-1.6]]].into_dyn());
+    assert_eq!(array(&max(&x, None, true)).shape(), &[1, 1, 1]);
+}
+
+#[test]
+fn test_max_splits_gradient_between_ties() {
+    let x = Tensor::new(array![[3.0, 1.0, 3.0], [2.0, 2.0, 2.0]].into_dyn(), true);
+    sum(&max(&x, Some(vec![1]), false), None, false).backward();
+    assert_close(&grad(&x), &array![[0.5, 0.0, 0.5], [1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0]].into_dyn(), 1e-6);
+}
+
+#[test]
+fn test_max_min_propagate_nan() {
+    let x = Tensor::new(array![[1.0, f32::NAN, 3.0], [f32::NAN, -2.0, 0.0], [4.0, 5.0, 6.0]].into_dyn(), false);
+
+    for reduced in [max(&x, Some(vec![1]), false), min(&x, Some(vec![1]), false)] {
+        let out = array(&reduced);
+        assert!(out[0].is_nan() && out[1].is_nan() && !out[2].is_nan());
+    }
+    let norm_inf = Tensor::data(&norm(&x, f32::INFINITY, None, false)).to_array();
+    assert!(norm_inf.iter().all(|v| v.is_nan()));
+    assert_eq!(array(&argmax(&x, Some(1), false)), array![1.0, 0.0, 2.0].into_dyn());
+    assert_eq!(array(&argmin(&x, Some(1), false)), array![1.0, 0.0, 0.0].into_dyn());
+}
+
+#[test]
+fn test_argmax_argmin_and_indices() {
+    let x = Tensor::new(array![[1.0, 5.0, 5.0], [7.0, -2.0, 0.0]].into_dyn(), false);
+
+    assert_eq!(array(&argmax(&x, Some(1), false)), array![1.0, 0.0].into_dyn());
+    assert_eq!(array(&argmin(&x, Some(0), true)), array![[0.0, 1.0, 1.0]].into_dyn());
+    assert!(&Tensor::data(&argmax(&x, None, false)) == &TensorData::Scalar(3.0));
+
+    let (values, indices) = min_with_indices(&x, 1, false);
+    assert_eq!(array(&values), array![1.0, -2.0].into_dyn());
+    assert_eq!(array(&indices), array![0.0, 1.0].into_dyn());
+    let (values, indices) = max_with_indices(&x, 0, true);
+    assert_eq!(array(&values), array![[7.0, 5.0, 5.0]].into_dyn());
+    assert_eq!(array(&indices), array![[1.0, 0.0, 0.0]].into_dyn());
+}
+
+#[test]
+fn test_arg_reductions_are_detached() {
+    let x = Tensor::new(array![[1.0, 5.0], [7.0, -2.0]].into_dyn(), true);
+    let eager = argmax(&x, Some(1), false);
+    let lazy = nanograd_rs::tensor::lazy(|| argmin(&x, None, false));
+
+    for indices in [eager, lazy] {
+        let t = indices.borrow();
+        assert!(!t.requires_grad && t.grad_fn.is_none() && t.parents.is_empty());
+    }
+}
+
+#[test]
+fn test_prod_gradient_with_zeros() {
+    let x = Tensor::new(array![[2.0, 3.0, 4.0], [2.0, 0.0, 5.0], [0.0, 1.0, 0.0]].into_dyn(), true);
+    let p = prod(&x, Some(vec![1]), false);
+    assert_eq!(array(&p), array![24.0, 0.0, 0.0].into_dyn());
+
+    sum(&p, None, false).backward();
+    assert_eq!(grad(&x), array![[12.0, 8.0, 6.0], [0.0, 10.0, 0.0], [0.0, 0.0, 0.0]].into_dyn());
+}
+
+#[test]
+fn test_var_and_std() {
+    let x = Tensor::new(array![[1.0, 2.0, 3.0, 4.0], [2.0, 2.0, 2.0, 2.0]].into_dyn(), false);
+
+    assert_close(&array(&var(&x, Some(vec![1]), true, false)), &array![5.0 / 3.0, 0.0].into_dyn(), 1e-6);
+    assert_close(&array(&var(&x, Some(vec![1]), false, false)), &array![1.25, 0.0].into_dyn(), 1e-6);
+    assert_close(&array(&std(&x, Some(vec![1]), true, true)), &array![[(5.0f32 / 3.0).sqrt()], [0.0]].into_dyn(), 1e-6);
+
+    // A constant lane has a zero, not NaN, gradient.
+    let x = Tensor::new(array![2.0, 2.0, 2.0].into_dyn(), true);
+    std(&x, None, true, false).backward();
+    assert_eq!(grad(&x), array![0.0, 0.0, 0.0].into_dyn());
+}
+
+#[test]
+fn test_logsumexp_and_norm() {
+    let x = Tensor::new(array![[1000.0, 1000.0], [-3.0, 4.0]].into_dyn(), false);
+    assert_close(&array(&logsumexp(&x, Some(vec![1]), false)), &array![1000.0 + 2.0f32.ln(), 4.0 + (-7.0f32).exp().ln_1p()].into_dyn(), 1e-6);
+
+    let v = Tensor::new(array![3.0, -4.0, 0.0].into_dyn(), false);
+    assert!(&Tensor::data(&norm(&v, 2.0, None, false)) == &TensorData::Scalar(5.0));
+    assert!(&Tensor::data(&norm(&v, 1.0, None, false)) == &TensorData::Scalar(7.0));
+    assert!(&Tensor::data(&norm(&v, f32::INFINITY, None, false)) == &TensorData::Scalar(4.0));
+}
+
+#[test]
+fn test_reduction_gradcheck() {
+    let weights = array![[1.0, -2.0], [0.5, 3.0]].into_dyn();
+    let weighted = |f: fn(&TensorRef) -> TensorRef| {
+        gradcheck(|t| mul(&f(&t[0]), &t[1]), &[values(), weights.clone()]);
+    };
+
+    weighted(|a| max(a, Some(vec![2]), false));
+    weighted(|a| min(a, Some(vec![2]), false));
+    weighted(|a| prod(a, Some(vec![2]), false));
+    weighted(|a| var(a, Some(vec![2]), true, false));
+    weighted(|a| std(a, Some(vec![2]), false, false));
+    weighted(|a| logsumexp(a, Some(vec![2]), false));
+    weighted(|a| norm(a, 3.0, Some(vec![2]), false));
+    weighted(|a| norm(a, f32::INFINITY, Some(vec![2]), false));
+
+    // Several reduced axes at once, keeping dims.
+    gradcheck(|t| var(&t[0], Some(vec![0, 2]), true, true), &[values()]);
+    gradcheck(|t| logsumexp(&t[0], None, false), &[values()]);
+}
+
+#[test]
+fn test_negative_and_duplicate_axes() {
+    let x = Tensor::new(values(), false);
+
+    assert_eq!(array(&sum(&x, Some(vec![-1]), false)), array(&sum(&x, Some(vec![2]), false)));
+    assert_eq!(array(&mean(&x, Some(vec![-3, 2]), true)), array(&mean(&x, Some(vec![0, 2]), true)));
+    assert_eq!(array(&max(&x, Some(vec![1, -2, 1]), false)), array(&max(&x, Some(vec![1]), false)));
+    assert_eq!(array(&argmin(&x, Some(-1), false)), array(&argmin(&x, Some(2), false)));
+}
+
+#[test]
+fn test_backward_with_unsorted_axes() {
+    let x = Tensor::new(ndarray::Array::range(0.0, 24.0, 1.0).into_shape_with_order(vec![2, 3, 4]).unwrap(), true);
+    let weights = Tensor::new(array![1.0, 2.0, 3.0].into_dyn(), false);
+    sum(&mul(&sum(&x, Some(vec![2, 0]), false), &weights), None, false).backward();
+    assert_eq!(grad(&x), array![[1.0, 2.0, 3.0]].into_dyn().into_shape_with_order(vec![1, 3, 1]).unwrap().broadcast(vec![2, 3, 4]).unwrap().to_owned());
+
+    let x = Tensor::new(values(), true);
+    mean(&x, None, true).backward();
+    assert_close(&grad(&x), &ArrayD::from_elem(vec![2, 2, 3], 1.0 / 12.0), 1e-7);
+}
+
+#[test]
+#[should_panic(expected = "Axis -4 out of range for a 3-d tensor")]
+fn test_reduction_rejects_out_of_range_axis() {
+    sum(&Tensor::new(values(), false), Some(vec![-4]), false);
+}
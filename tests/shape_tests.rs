@@ -0,0 +1,173 @@
+mod common;
+
+use common::{arange, array, grad, gradcheck};
+use nanograd_rs::ops::{reshape, flatten, squeeze, unsqueeze, expand, broadcast_to, permute, transpose, pad, add, mul, sum, PadMode};
+use nanograd_rs::tensor::{Tensor, TensorData, TensorOps};
+use ndarray::{arr1, arr2, ArrayD};
+
+#[test]
+fn test_reshape_infers_dimension() {
+    let x = Tensor::new(arange(&[2, 3, 4]), true);
+    let y = reshape(&x, &[-1, 6]);
+
+    assert_eq!(array(&y).shape(), &[4, 6]);
+    assert_eq!(array(&y)[[1, 0]], 6.0);
+
+    sum(&mul(&y, &y), None, false).backward();
+    assert_eq!(grad(&x), arange(&[2, 3, 4]) * 2.0);
+}
+
+#[test]
+#[should_panic(expected = "Cannot infer dimension")]
+fn test_reshape_rejects_bad_size() {
+    reshape(&Tensor::new(arange(&[2, 3]), false), &[4, -1]);
+}
+
+#[test]
+fn test_flatten_before_linear() {
+    let x = Tensor::new(arange(&[2, 3, 2, 2]), false);
+    assert_eq!(array(&flatten(&x, 1, 3)).shape(), &[2, 12]);
+    assert_eq!(array(&flatten(&x, 0, 1)).shape(), &[6, 2, 2]);
+
+    gradcheck(|t| mul(&flatten(&t[0], 1, 2), &flatten(&t[0], 1, 2)), &[arange(&[2, 2, 3]) / 10.0]);
+}
+
+#[test]
+fn test_squeeze_unsqueeze_roundtrip() {
+    let x = Tensor::new(arange(&[3]), true);
+    let col = unsqueeze(&x, 1);
+    assert_eq!(array(&col).shape(), &[3, 1]);
+
+    let back = squeeze(&col, 1);
+    assert_eq!(array(&back), arange(&[3]));
+
+    let one = Tensor::new(arr1(&[5.0]).into_dyn(), false);
+    assert!(&Tensor::data(&squeeze(&one, 0)) == &TensorData::Scalar(5.0));
+
+    sum(&back, None, false).backward();
+    assert_eq!(grad(&x), ArrayD::ones(vec![3]));
+}
+
+#[test]
+#[should_panic(expected = "Cannot squeeze axis 0 of size 3")]
+fn test_squeeze_rejects_non_unit_axis() {
+    squeeze(&Tensor::new(arange(&[3]), false), 0);
+}
+
+#[test]
+fn test_expand_sums_gradient() {
+    let x = Tensor::new(arr2(&[[1.0], [2.0]]).into_dyn(), true);
+    let y = expand(&x, &[3, -1, 4]);
+
+    assert_eq!(array(&y).shape(), &[3, 2, 4]);
+    sum(&y, None, false).backward();
+    assert_eq!(grad(&x), arr2(&[[12.0], [12.0]]).into_dyn());
+
+    gradcheck(|t| mul(&broadcast_to(&t[0], &[2, 3]), &t[1]), &[arange(&[3]), arange(&[2, 3]) - 2.0]);
+}
+
+#[test]
+#[should_panic(expected = "Invalid dimension in expand shape")]
+fn test_expand_rejects_negative_size() {
+    expand(&Tensor::new(arange(&[3]), false), &[-2, 3]);
+}
+
+#[test]
+fn test_implicit_broadcast_gradients() {
+    let bias = Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), true);
+    let scale = Tensor::new(2.0, true);
+    let x = Tensor::new(arange(&[2, 3]), false);
+
+    sum(&mul(&add(&x, &bias), &scale), None, false).backward();
+
+    assert_eq!(grad(&bias), arr1(&[4.0, 4.0, 4.0]).into_dyn());
+    assert!(&scale.borrow().grad.clone().unwrap() == &TensorData::Scalar(27.0));
+}
+
+#[test]
+fn test_transpose_and_t() {
+    let x = Tensor::new(arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn(), true);
+    let expected = arr2(&[[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]).into_dyn();
+
+    assert_eq!(array(&transpose(&x, 0, 1)), expected);
+    assert_eq!(array(&x.t()), expected);
+    assert_eq!(array(&Tensor::new(arange(&[3]), false).t()), arange(&[3]));
+
+    let w = Tensor::new(arr2(&[[1.0, 0.0], [2.0, 1.0], [0.0, 3.0]]).into_dyn(), false);
+    sum(&mul(&x.t(), &w), None, false).backward();
+    assert_eq!(grad(&x), arr2(&[[1.0, 2.0, 0.0], [0.0, 1.0, 3.0]]).into_dyn());
+}
+
+#[test]
+fn test_permute_gradcheck() {
+    let x = Tensor::new(arange(&[2, 3, 4]), false);
+    let y = permute(&x, &[2, 0, 1]);
+
+    assert_eq!(array(&y).shape(), &[4, 2, 3]);
+    assert_eq!(array(&y)[[3, 1, 2]], array(&x)[[1, 2, 3]]);
+
+    let weights = arange(&[4, 2, 3]) - 5.0;
+    gradcheck(|t| mul(&permute(&t[0], &[2, 0, 1]), &t[1]), &[arange(&[2, 3, 4]) / 10.0, weights]);
+    gradcheck(|t| mul(&transpose(&t[0], 0, 2), &transpose(&t[0], 0, 2)), &[arange(&[2, 3, 4]) / 10.0]);
+}
+
+#[test]
+#[should_panic(expected = "is not a permutation")]
+fn test_permute_rejects_invalid_order() {
+    permute(&Tensor::new(arange(&[2, 3]), false), &[0, 0]);
+}
+
+#[test]
+fn test_pad_modes() {
+    let x = Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), false);
+
+    assert_eq!(array(&pad(&x, &[(2, 1)], PadMode::Constant(-1.0))), arr1(&[-1.0, -1.0, 1.0, 2.0, 3.0, -1.0]).into_dyn());
+    assert_eq!(array(&pad(&x, &[(2, 1)], PadMode::Reflect)), arr1(&[3.0, 2.0, 1.0, 2.0, 3.0, 2.0]).into_dyn());
+    assert_eq!(array(&pad(&x, &[(2, 1)], PadMode::Replicate)), arr1(&[1.0, 1.0, 1.0, 2.0, 3.0, 3.0]).into_dyn());
+    assert_eq!(array(&pad(&x, &[(2, 1)], PadMode::Circular)), arr1(&[2.0, 3.0, 1.0, 2.0, 3.0, 1.0]).into_dyn());
+}
+
+#[test]
+fn test_pad_trailing_dims_only() {
+    let x = Tensor::new(arr2(&[[1.0, 2.0], [3.0, 4.0]]).into_dyn(), false);
+
+    let rows = array(&pad(&x, &[(0, 1)], PadMode::Constant(0.0)));
+    assert_eq!(rows, arr2(&[[1.0, 2.0, 0.0], [3.0, 4.0, 0.0]]).into_dyn());
+
+    let both = array(&pad(&x, &[(1, 0), (0, 1)], PadMode::Replicate));
+    assert_eq!(both, arr2(&[[1.0, 2.0, 2.0], [1.0, 2.0, 2.0], [3.0, 4.0, 4.0]]).into_dyn());
+}
+
+#[test]
+fn test_pad_backward_crops_and_folds() {
+    let constant = Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), true);
+    sum(&pad(&constant, &[(2, 2)], PadMode::Constant(5.0)), None, false).backward();
+    assert_eq!(grad(&constant), arr1(&[1.0, 1.0, 1.0]).into_dyn());
+
+    // Each padded position adds its gradient to the element it copied.
+    let weights = Tensor::new(arr1(&[1.0, 10.0, 100.0, 1000.0, 10000.0, 100000.0]).into_dyn(), false);
+    let expected = [
+        (PadMode::Reflect, [100.0, 10.0 + 1000.0 + 100000.0, 1.0 + 10000.0]),
+        (PadMode::Replicate, [111.0, 1000.0, 110000.0]),
+        (PadMode::Circular, [100.0 + 100000.0, 1000.0 + 1.0, 10000.0 + 10.0])
+    ];
+    for (mode, expected) in expected {
+        let x = Tensor::new(arr1(&[1.0, 2.0, 3.0]).into_dyn(), true);
+        sum(&mul(&pad(&x, &[(2, 1)], mode), &weights), None, false).backward();
+        assert_eq!(grad(&x), arr1(&expected).into_dyn(), "{:?}", mode);
+    }
+}
+
+#[test]
+fn test_pad_gradcheck() {
+    let w = arange(&[2, 6, 7]).mapv(|v| v * 0.1 - 1.0);
+    for mode in [PadMode::Constant(0.5), PadMode::Reflect, PadMode::Replicate, PadMode::Circular] {
+        gradcheck(|t| mul(&pad(&t[0], &[(1, 2), (2, 1)], mode), &t[1]), &[arange(&[2, 3, 4]), w.clone()]);
+    }
+}
+
+#[test]
+#[should_panic(expected = "must be smaller than the axis size")]
+fn test_pad_rejects_large_reflect() {
+    pad(&Tensor::new(arr1(&[1.0, 2.0]).into_dyn(), false), &[(2, 0)], PadMode::Reflect);
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::{array, assert_close, grad, gradcheck};
+use nanograd_rs::ops::{log_softmax, mul, softmax, sum};
+use nanograd_rs::tensor::{Tensor, TensorOps};
+use ndarray::{array, ArrayD, Axis};
+
+fn logits() -> ArrayD<f32> {
+    array![[1.0, 2.0, 0.5], [-1.0, 0.0, 3.0]].into_dyn()
+}
+
+#[test]
+fn test_softmax_values() {
+    let y = array(&softmax(&Tensor::new(logits(), false), 1));
+    let exps = logits().mapv(f32::exp);
+    let expected = &exps / &exps.sum_axis(Axis(1)).insert_axis(Axis(1));
+    assert_close(&y, &expected, 1e-6);
+
+    let columns = array(&softmax(&Tensor::new(logits(), false), 0));
+    assert_close(&columns.sum_axis(Axis(0)), &array![1.0, 1.0, 1.0].into_dyn(), 1e-6);
+
+    // Negative axes count from the end.
+    assert_close(&array(&softmax(&Tensor::new(logits(), false), -1)), &y, 1e-6);
+    assert_close(&array(&log_softmax(&Tensor::new(logits(), false), -2)), &columns.mapv(f32::ln), 1e-5);
+}
+
+#[test]
+fn test_log_softmax_matches_log_of_softmax() {
+    let x = Tensor::new(logits(), false);
+    assert_close(&array(&log_softmax(&x, 1)), &array(&softmax(&x, 1)).mapv(f32::ln), 1e-5);
+}
+
+#[test]
+fn test_large_logits_do_not_overflow() {
+    let x = Tensor::new(array![[1000.0, 1001.0, 999.0], [-1000.0, 0.0, 1000.0]].into_dyn(), true);
+    let shifted = Tensor::new(array![[1.0, 2.0, 0.0], [-2000.0, -1000.0, 0.0]].into_dyn(), false);
+
+    assert_close(&array(&softmax(&x, 1)), &array(&softmax(&shifted, 1)), 1e-6);
+    let log_probs = array(&log_softmax(&x, 1));
+    assert!(log_probs.iter().all(|v| v.is_finite()));
+    assert_close(&log_probs, &array![[-1.407_606, -0.407_606, -2.407_606], [-2000.0, -1000.0, 0.0]].into_dyn(), 1e-5);
+
+    sum(&log_softmax(&x, 1), None, false).backward();
+    assert!(grad(&x).iter().all(|v| v.is_finite()));
+}
+
+#[test]
+fn test_nan_logit_poisons_only_its_lane() {
+    let x = Tensor::new(array![[1.0, f32::NAN, 0.5], [-1.0, 0.0, 3.0]].into_dyn(), false);
+
+    for out in [array(&softmax(&x, 1)), array(&log_softmax(&x, 1))] {
+        assert!(out.index_axis(Axis(0), 0).iter().all(|v| v.is_nan()));
+        assert!(out.index_axis(Axis(0), 1).iter().all(|v| v.is_finite()));
+    }
+}
+
+#[test]
+fn test_softmax_gradcheck() {
+    let weights = array![[0.3, -1.2, 2.0], [1.5, 0.2, -0.7]].into_dyn();
+    gradcheck(|t| mul(&softmax(&t[0], 1), &t[1]), &[logits(), weights.clone()]);
+    gradcheck(|t| mul(&softmax(&t[0], 0), &t[1]), &[logits(), weights.clone()]);
+    gradcheck(|t| mul(&log_softmax(&t[0], 1), &t[1]), &[logits(), weights.clone()]);
+    gradcheck(|t| mul(&log_softmax(&t[0], 0), &t[1]), &[logits(), weights.clone()]);
+    gradcheck(|t| mul(&log_softmax(&t[0], -1), &t[1]), &[logits(), weights]);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn test_softmax_rejects_bad_axis() {
+    softmax(&Tensor::new(logits(), false), 2);
+}
+
+#[test]
+#[should_panic(expected = "Axis -3 out of range for a 2-d tensor")]
+fn test_softmax_rejects_bad_negative_axis() {
+    log_softmax(&Tensor::new(logits(), false), -3);
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::{array, grad, gradcheck};
+use nanograd_rs::ops::*;
+use nanograd_rs::tensor::{Tensor, TensorOps};
+use ndarray::array;
+
+#[test]
+fn test_sort_values_and_indices() {
+    let x = Tensor::new(array![[3.0, 1.0, 2.0], [0.5, 4.0, -1.0]].into_dyn(), false);
+
+    let (values, indices) = sort(&x, 1, false);
+    assert_eq!(array(&values), array![[1.0, 2.0, 3.0], [-1.0, 0.5, 4.0]].into_dyn());
+    assert_eq!(array(&indices), array![[1.0, 2.0, 0.0], [2.0, 0.0, 1.0]].into_dyn());
+
+    let (values, indices) = sort(&x, 0, true);
+    assert_eq!(array(&values), array![[3.0, 4.0, 2.0], [0.5, 1.0, -1.0]].into_dyn());
+    assert_eq!(array(&indices), array![[0.0, 1.0, 0.0], [1.0, 0.0, 1.0]].into_dyn());
+
+    assert_eq!(array(&argsort(&x, -1, true)), array![[0.0, 2.0, 1.0], [1.0, 0.0, 2.0]].into_dyn());
+}
+
+#[test]
+fn test_sort_is_stable() {
+    let x = Tensor::new(array![2.0, 1.0, 2.0, 1.0].into_dyn(), false);
+    assert_eq!(array(&argsort(&x, 0, false)), array![1.0, 3.0, 0.0, 2.0].into_dyn());
+    assert_eq!(array(&argsort(&x, 0, true)), array![0.0, 2.0, 1.0, 3.0].into_dyn());
+}
+
+#[test]
+fn test_topk() {
+    let x = Tensor::new(array![[0.1, 0.9, 0.4, 0.7], [0.3, 0.2, 0.8, 0.5]].into_dyn(), true);
+    let (values, indices) = topk(&x, 2, 1);
+    assert_eq!(array(&values), array![[0.9, 0.7], [0.8, 0.5]].into_dyn());
+    assert_eq!(array(&indices), array![[1.0, 3.0], [2.0, 3.0]].into_dyn());
+
+    // Only the selected entries receive gradient, at their original positions.
+    let weights = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn(), false);
+    sum(&mul(&values, &weights), None, false).backward();
+    assert_eq!(grad(&x), array![[0.0, 1.0, 0.0, 2.0], [0.0, 0.0, 3.0, 4.0]].into_dyn());
+}
+
+#[test]
+fn test_sort_gradcheck() {
+    let x = array![[0.3, -1.2, 2.0, 0.8], [1.4, 0.1, -0.7, 0.6]].into_dyn();
+    let w = array![[1.0, -2.0, 0.5, 3.0], [0.2, 1.5, -1.0, 2.0]].into_dyn();
+    gradcheck(|t| mul(&sort(&t[0], 1, false).0, &t[1]), &[x.clone(), w.clone()]);
+    gradcheck(|t| mul(&sort(&t[0], 0, true).0, &t[1]), &[x.clone(), w]);
+    gradcheck(|t| mul(&topk(&t[0], 3, -1).0, &t[1]), &[x, array![[1.0, 2.0, 3.0], [-1.0, 0.5, 2.0]].into_dyn()]);
+}
+
+#[test]
+fn test_sort_in_lazy_mode() {
+    let x = Tensor::new(array![3.0, 1.0, 2.0].into_dyn(), true);
+    let (values, indices) = nanograd_rs::tensor::lazy(|| topk(&x, 2, 0));
+    sum(&values, None, false).backward();
+
+    assert_eq!(array(&indices), array![0.0, 2.0].into_dyn());
+    assert_eq!(grad(&x), array![1.0, 0.0, 1.0].into_dyn());
+}
+
+#[test]
+#[should_panic(expected = "larger than the axis size")]
+fn test_topk_rejects_large_k() {
+    topk(&Tensor::new(array![1.0, 2.0].into_dyn(), false), 3, 0);
+}
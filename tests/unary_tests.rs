@@ -0,0 +1,150 @@
+mod common;
+
+use common::{array, assert_close, grad, gradcheck};
+use nanograd_rs::ops::*;
+use nanograd_rs::tensor::{Tensor, TensorOps, TensorRef};
+use ndarray::{array, ArrayD};
+
+fn values() -> ArrayD<f32> {
+    array![[-1.3, -0.4, 0.2], [0.7, 1.1, 2.5]].into_dyn()
+}
+
+fn positive() -> ArrayD<f32> {
+    array![[0.3, 0.8, 1.2], [1.9, 2.4, 3.7]].into_dyn()
+}
+
+type UnaryOp = fn(&TensorRef) -> TensorRef;
+
+#[test]
+fn test_elementwise_forward_matches_std() {
+    let cases = [
+        (exp as UnaryOp, f32::exp as fn(f32) -> f32),
+        (log, f32::ln),
+        (log1p, f32::ln_1p),
+        (sqrt, f32::sqrt),
+        (rsqrt, |x| 1.0 / x.sqrt()),
+        (sin, f32::sin),
+        (cos, f32::cos),
+        (tan, f32::tan),
+        (tanh, f32::tanh),
+        (sigmoid, |x| 1.0 / (1.0 + (-x).exp())),
+        (reciprocal, f32::recip),
+        (square, |x| x * x)
+    ];
+
+    for (op, reference) in cases {
+        let x = positive();
+        assert_close(&array(&op(&Tensor::new(x.clone(), false))), &x.mapv(reference), 1e-6);
+    }
+}
+
+#[test]
+fn test_elementwise_gradcheck() {
+    for op in [exp, sin, cos, tan, tanh, sigmoid, square] {
+        gradcheck(|t| op(&t[0]), &[values()]);
+    }
+    for op in [log, log1p, sqrt, rsqrt, reciprocal] {
+        gradcheck(|t| op(&t[0]), &[positive()]);
+    }
+}
+
+#[test]
+fn test_sigmoid_is_stable_for_large_inputs() {
+    let x = Tensor::new(array![-100.0, 0.0, 100.0].into_dyn(), true);
+    let y = sigmoid(&x);
+    assert_close(&array(&y), &array![0.0, 0.5, 1.0].into_dyn(), 1e-6);
+
+    sum(&y, None, false).backward();
+    assert_close(&grad(&x), &array![0.0, 0.25, 0.0].into_dyn(), 1e-6);
+}
+
+#[test]
+fn test_pow_scalar_and_tensor_exponent() {
+    gradcheck(|t| pow_scalar(&t[0], 3.0), &[values()]);
+    gradcheck(|t| pow_scalar(&t[0], -0.5), &[positive()]);
+    gradcheck(|t| pow(&t[0], &t[1]), &[positive(), values()]);
+
+    // A [3] exponent broadcasts against a [2, 3] base.
+    let base = Tensor::new(positive(), true);
+    let exponent = Tensor::new(array![1.0, 2.0, 0.5].into_dyn(), true);
+    let y = pow(&base, &exponent);
+    let mut expected = positive();
+    for mut row in expected.rows_mut() {
+        row[1] = row[1] * row[1];
+        row[2] = row[2].sqrt();
+    }
+    assert_close(&array(&y), &expected, 1e-6);
+    sum(&y, None, false).backward();
+    assert_eq!(grad(&exponent).shape(), &[3]);
+}
+
+#[test]
+fn test_pow_at_zero_base() {
+    let x = Tensor::new(array![0.0, 2.0].into_dyn(), true);
+    let p = Tensor::new(array![2.0, 0.0].into_dyn(), true);
+    sum(&pow(&x, &p), None, false).backward();
+
+    assert_eq!(grad(&x), array![0.0, 0.0].into_dyn());
+    assert_close(&grad(&p), &array![0.0, 2.0f32.ln()].into_dyn(), 1e-6);
+
+    let x = Tensor::new(array![0.0, 3.0].into_dyn(), true);
+    sum(&pow_scalar(&x, 0.0), None, false).backward();
+    assert_eq!(grad(&x), array![0.0, 0.0].into_dyn());
+}
+
+// Regression: ReLU and Abs used to derive their mask and sign from the
+// upstream gradient instead of the input, so a negative upstream gradient
+// was zeroed (ReLU) or flipped to positive (Abs).
+#[test]
+fn test_relu_abs_gradient_uses_input_not_upstream_sign_regression() {
+    let x = Tensor::new(array![-2.0, 0.0, 3.0].into_dyn(), true);
+    let upstream = Tensor::new(array![-1.0, -1.0, -1.0].into_dyn(), false);
+    sum(&mul(&relu(&x), &upstream), None, false).backward();
+    assert_eq!(grad(&x), array![0.0, 0.0, -1.0].into_dyn());
+
+    let x = Tensor::new(array![-2.0, 0.0, 3.0].into_dyn(), true);
+    sum(&mul(&abs(&x), &upstream), None, false).backward();
+    assert_eq!(grad(&x), array![1.0, 0.0, -1.0].into_dyn());
+
+    gradcheck(|t| mul(&relu(&t[0]), &t[1]), &[values(), -positive()]);
+    gradcheck(|t| mul(&abs(&t[0]), &t[1]), &[values(), -positive()]);
+}
+
+#[test]
+fn test_activation_values() {
+    let x = || Tensor::new(array![-4.0, -1.0, 0.5, 7.0].into_dyn(), false);
+
+    assert_close(&array(&leaky_relu(&x(), 0.1)), &array![-0.4, -0.1, 0.5, 7.0].into_dyn(), 1e-6);
+    assert_close(&array(&relu6(&x())), &array![0.0, 0.0, 0.5, 6.0].into_dyn(), 1e-6);
+    assert_close(&array(&hardtanh(&x(), -2.0, 2.0)), &array![-2.0, -1.0, 0.5, 2.0].into_dyn(), 1e-6);
+    assert_close(&array(&hardswish(&x())), &array![0.0, -1.0 / 3.0, 0.5 * 3.5 / 6.0, 7.0].into_dyn(), 1e-6);
+    assert_close(&array(&elu(&x(), 1.0)), &array![(-4.0f32).exp_m1(), (-1.0f32).exp_m1(), 0.5, 7.0].into_dyn(), 1e-6);
+    assert_close(&array(&selu(&x())), &array![-1.725_898_6, -1.111_330_7, 0.525_350_5, 7.354_907].into_dyn(), 1e-5);
+    assert_close(&array(&gelu(&x(), false)), &array![-1.2668e-4, -0.158_655_25, 0.345_731_23, 7.0].into_dyn(), 1e-4);
+    assert_close(&array(&gelu(&x(), true)), &array![-7.0246e-5, -0.158_808, 0.345_714, 7.0].into_dyn(), 1e-4);
+    assert_close(&array(&silu(&x())), &array(&swish(&x())), 0.0);
+    assert_close(&array(&silu(&x())), &array![-0.071_944_84, -0.268_941_4, 0.311_229_66, 6.993_623].into_dyn(), 1e-5);
+    assert_close(&array(&mish(&x())), &array![-0.072_591_74, -0.303_401_46, 0.375_245_2, 6.999_988].into_dyn(), 1e-5);
+    assert_close(&array(&softplus(&x(), 2.0, 10.0)), &array![1.6770e-4, 0.063_464, 0.656_630_8, 7.0].into_dyn(), 1e-4);
+}
+
+#[test]
+fn test_activations_are_stable_for_large_inputs() {
+    let x = Tensor::new(array![-1000.0, 1000.0].into_dyn(), true);
+    for y in [silu(&x), mish(&x), softplus(&x, 1.0, 2000.0), elu(&x, 1.0), gelu(&x, true)] {
+        assert!(array(&y).iter().all(|v| v.is_finite()));
+    }
+}
+
+#[test]
+fn test_activation_gradcheck() {
+    for op in [selu, silu, mish, hardswish] {
+        gradcheck(|t| op(&t[0]), &[values()]);
+    }
+    gradcheck(|t| leaky_relu(&t[0], 0.2), &[values()]);
+    gradcheck(|t| elu(&t[0], 1.5), &[values()]);
+    gradcheck(|t| gelu(&t[0], false), &[values()]);
+    gradcheck(|t| gelu(&t[0], true), &[values()]);
+    gradcheck(|t| softplus(&t[0], 2.0, 3.0), &[values()]);
+    gradcheck(|t| hardtanh(&t[0], -1.0, 1.0), &[values()]);
+}
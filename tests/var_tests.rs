@@ -0,0 +1,58 @@
+use nanograd_rs::var::Var;
+use nanograd_rs::tensor::TensorData;
+
+fn scalar(v: &Var) -> f32 {
+    match v.data() {
+        TensorData::Scalar(x) => x,
+        TensorData::Tensor(_) => panic!("expected scalar")
+    }
+}
+
+fn grad(v: &Var) -> f32 {
+    match v.grad() {
+        Some(TensorData::Scalar(x)) => x,
+        other => panic!("expected scalar gradient, got {:?}", other)
+    }
+}
+
+#[test]
+fn test_operators_build_graph() {
+    // f(a, x, b) = a * x + b
+    let a = Var::new(2.0, true);
+    let x = Var::new(5.0, true);
+    let b = Var::new(-3.0, true);
+
+    let f = &a * &x + &b;
+    f.backward();
+
+    assert_eq!(scalar(&f), 7.0);
+    assert_eq!(grad(&a), 5.0);
+    assert_eq!(grad(&x), 2.0);
+    assert_eq!(grad(&b), 1.0);
+}
+
+#[test]
+fn test_owned_and_borrowed_operands() {
+    // f(x) = -(x - 1) / (x * x)
+    // f'(x) = (x - 2) / x^3, f'(2) = 0
+    let x = Var::new(2.0, true);
+
+    let f = -(&x - Var::new(1.0, false)) / (x.clone() * &x);
+    f.backward();
+
+    assert_eq!(scalar(&f), -0.25);
+    assert_eq!(grad(&x), 0.0);
+}
+
+#[test]
+fn test_scalar_mixes() {
+    // f(x) = 2 * x + x / 4 - 1 + (3 - x)
+    // f'(x) = 2 + 0.25 - 1 = 1.25
+    let x = Var::new(8.0, true);
+
+    let f = 2.0 * &x + &x / 4.0 - 1.0 + (3.0 - &x);
+    f.backward();
+
+    assert_eq!(scalar(&f), 12.0);
+    assert_eq!(grad(&x), 1.25);
+}